@@ -0,0 +1,199 @@
+use super::*;
+use alloy::dyn_abi::{DynSolValue, JsonAbiExt, Specifier};
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::Bytes;
+
+/// Errors for offline ABI encoding of contract calls.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to parse the function signature or JSON ABI: {0}
+    Parse(eyre::Report),
+    /// No function named `{0}` taking {1} argument(s) was found in the ABI
+    FunctionNotFound(String, usize),
+    /// No function with signature `{0}` was found in the ABI
+    SignatureNotFound(String),
+    /// `{0}` has several overloads taking {1} argument(s) ({2}); pass the full signature instead
+    AmbiguousOverload(String, usize, String),
+    /// Failed to ABI-encode the function arguments: {0}
+    Encode(eyre::Report),
+}
+
+/// Parse a human-readable function signature such as `transfer(address,uint256)`.
+///
+/// # Errors
+///
+/// Returns [`Error::Parse`] if the signature is malformed.
+pub fn parse_signature(signature: &str) -> Result<Function, Error> {
+    Function::parse(signature).map_err(|e| Error::Parse(eyre::eyre!(e)))
+}
+
+/// Parse a JSON ABI, either a bare ABI array or a compiler artifact with an `abi` field.
+///
+/// # Errors
+///
+/// Returns [`Error::Parse`] if the JSON is not a valid ABI.
+pub fn parse_json_abi(json: &str) -> Result<JsonAbi, Error> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| Error::Parse(eyre::eyre!(e)))?;
+    let abi = match value {
+        serde_json::Value::Object(mut artifact) if artifact.contains_key("abi") => {
+            artifact.remove("abi").unwrap_or_default()
+        }
+        abi => abi,
+    };
+    serde_json::from_value(abi).map_err(|e| Error::Parse(eyre::eyre!(e)))
+}
+
+/// Look up function `name` in `abi`, either by full signature such as `set(uint256,bool)`
+/// or by bare name and `arity`.
+///
+/// # Errors
+///
+/// Returns [`Error::Parse`] for a malformed signature, [`Error::SignatureNotFound`] if the
+/// ABI has no function with that signature, [`Error::FunctionNotFound`] if no overload with
+/// that name and arity exists, and [`Error::AmbiguousOverload`] if several do.
+pub fn find_function<'a>(
+    abi: &'a JsonAbi,
+    name: &str,
+    arity: usize,
+) -> Result<&'a Function, Error> {
+    if name.contains('(') {
+        let signature = parse_signature(name)?.signature();
+        return abi
+            .functions()
+            .find(|function| function.signature() == signature)
+            .ok_or(Error::SignatureNotFound(signature));
+    }
+    let candidates: Vec<&Function> = abi
+        .function(name)
+        .into_iter()
+        .flatten()
+        .filter(|function| function.inputs.len() == arity)
+        .collect();
+    match candidates.as_slice() {
+        [] => Err(Error::FunctionNotFound(name.to_owned(), arity)),
+        [function] => Ok(function),
+        overloads => {
+            let signatures = overloads.iter().map(|function| function.signature());
+            let signatures = signatures.collect::<Vec<_>>().join(", ");
+            Err(Error::AmbiguousOverload(name.to_owned(), arity, signatures))
+        }
+    }
+}
+
+/// ABI-encode a call to `function`, coercing each textual argument to its declared type.
+///
+/// Arguments use Solidity literal syntax, e.g. `0x...` for addresses and bytes,
+/// `[1,2]` for arrays and `(a,b)` for tuples.
+///
+/// # Errors
+///
+/// Returns [`Error::Encode`] if the argument count mismatches or an argument
+/// cannot be coerced to its parameter type.
+pub fn encode_call<S: AsRef<str>>(function: &Function, args: &[S]) -> Result<Bytes, Error> {
+    let encode = |e: alloy::dyn_abi::Error| Error::Encode(eyre::eyre!(e));
+    if function.inputs.len() != args.len() {
+        return Err(Error::Encode(eyre::eyre!(
+            "`{}` expects {} argument(s), got {}",
+            function.signature(),
+            function.inputs.len(),
+            args.len()
+        )));
+    }
+    let values = function
+        .inputs
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| param.resolve()?.coerce_str(arg.as_ref()))
+        .collect::<Result<Vec<DynSolValue>, _>>()
+        .map_err(encode)?;
+    function.abi_encode_input(&values).map(Into::into).map_err(encode)
+}
+
+/// Format a decoded value in the Solidity literal syntax accepted by [`encode_call`].
+pub fn format_value(value: &DynSolValue) -> String {
+    let list = |values: &[DynSolValue]| values.iter().map(format_value).collect::<Vec<_>>();
+    match value {
+        DynSolValue::Bool(value) => value.to_string(),
+        DynSolValue::Int(value, _) => value.to_string(),
+        DynSolValue::Uint(value, _) => value.to_string(),
+        DynSolValue::FixedBytes(word, size) => alloy::hex::encode_prefixed(&word[..*size]),
+        DynSolValue::Address(address) => address.to_string(),
+        DynSolValue::Bytes(bytes) => alloy::hex::encode_prefixed(bytes),
+        DynSolValue::String(string) => format!("{string:?}"),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            format!("[{}]", list(values).join(","))
+        }
+        DynSolValue::Tuple(values) => format!("({})", list(values).join(",")),
+        other => other.as_word().map(alloy::hex::encode_prefixed).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_erc20_transfer_from_signature() -> eyre::Result<()> {
+        let function = parse_signature("transfer(address,uint256)")?;
+        let input =
+            encode_call(&function, &["0x70997970C51812dc3A010C7d01b50e0d17dc79C8", "1000"])?;
+        assert_eq!(
+            hex::encode(input),
+            "a9059cbb00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8\
+             00000000000000000000000000000000000000000000000000000000000003e8"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_overload_from_artifact_abi() -> eyre::Result<()> {
+        let json = r#"{"abi": [
+            {"type": "function", "name": "set", "inputs": [{"name": "x", "type": "uint256"}],
+             "outputs": [], "stateMutability": "nonpayable"},
+            {"type": "function", "name": "set",
+             "inputs": [{"name": "x", "type": "uint256"}, {"name": "ok", "type": "bool"}],
+             "outputs": [], "stateMutability": "nonpayable"}
+        ]}"#;
+        let abi = parse_json_abi(json)?;
+        let function = find_function(&abi, "set", 2)?;
+        assert_eq!(function.signature(), "set(uint256,bool)");
+        assert!(matches!(find_function(&abi, "set", 3), Err(Error::FunctionNotFound(_, 3))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_ambiguous_overload_unless_signature_given() -> eyre::Result<()> {
+        let json = r#"[
+            {"type": "function", "name": "set", "inputs": [{"name": "x", "type": "uint256"}],
+             "outputs": [], "stateMutability": "nonpayable"},
+            {"type": "function", "name": "set", "inputs": [{"name": "ok", "type": "bool"}],
+             "outputs": [], "stateMutability": "nonpayable"}
+        ]"#;
+        let abi = parse_json_abi(json)?;
+        assert!(matches!(find_function(&abi, "set", 1), Err(Error::AmbiguousOverload(_, 1, _))));
+        let function = find_function(&abi, "set(bool)", 1)?;
+        assert_eq!(function.signature(), "set(bool)");
+        assert!(matches!(find_function(&abi, "set(address)", 1), Err(Error::SignatureNotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn formats_values_as_solidity_literals() -> eyre::Result<()> {
+        let function = parse_signature("f(address,int8,bytes2,string,(bool,uint256[]))")?;
+        let args =
+            ["0x70997970C51812dc3A010C7d01b50e0d17dc79C8", "-5", "0xbeef", "hi", "(true,[1,2])"];
+        let input = encode_call(&function, &args)?;
+        let values = function.abi_decode_input(&input[4..])?;
+        let formatted: Vec<String> = values.iter().map(format_value).collect();
+        assert_eq!(formatted, [args[0], "-5", "0xbeef", "\"hi\"", "(true,[1,2])"]);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_argument_count_mismatch() -> eyre::Result<()> {
+        let function = parse_signature("approve(address,uint256)")?;
+        assert!(matches!(encode_call(&function, &["0x00"]), Err(Error::Encode(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use super::*;
+use alloy::primitives::{
+    Address, Bytes, TxHash,
+    utils::{format_ether, parse_ether},
+};
+use envelope::{Envelope, Metadata, Payload};
+use serde::{Deserialize, Deserializer};
+use sign::{SignerBackend, UnsignedTx};
+
+/// Errors for batch signing from a manifest.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to parse the manifest: {0}
+    Manifest(eyre::Report),
+    /// The manifest contains no payments
+    Empty,
+    /// Failed to sign row {0} of the manifest: {1}
+    Sign(usize, sign::Error),
+    /// Failed to wrap row {0} of the manifest in an envelope: {1}
+    Envelope(usize, envelope::Error),
+    /// Transaction {0} of the batch is invalid: {1}
+    Invalid(usize, eyre::Report),
+    /// Nonce {1} of {0} appears more than once in the batch
+    DuplicateNonce(Address, u64),
+}
+
+/// One row of a batch manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Payment {
+    /// Recipient address.
+    pub to: Address,
+    /// Amount to send, given in ETH (e.g. "0.01") in the manifest.
+    #[serde(rename = "eth", deserialize_with = "deserialize_ether")]
+    pub value: Wei,
+    /// Calldata passed to the recipient; empty for plain ETH transfers.
+    #[serde(default)]
+    pub data: Option<Bytes>,
+    /// Gas limit of this payment, overriding the batch default.
+    #[serde(default)]
+    pub gas_limit: Option<u64>,
+    /// Human-readable description recorded in the signed envelope.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+fn deserialize_ether<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Wei, D::Error> {
+    let eth = String::deserialize(deserializer)?;
+    parse_ether(eth.trim()).map_err(serde::de::Error::custom)
+}
+
+/// Parse a manifest of payments, either a JSON array or CSV with a header row.
+///
+/// CSV columns are `to`, `eth` and optionally `data`, `gasLimit` and `description`;
+/// JSON objects use the same keys. The ETH amount is a decimal string in both formats.
+///
+/// # Errors
+///
+/// Returns [`Error::Manifest`] for malformed rows and [`Error::Empty`] if there are none.
+pub fn parse_manifest(manifest: &str) -> Result<Vec<Payment>, Error> {
+    let payments: Vec<Payment> = if manifest.trim_start().starts_with('[') {
+        serde_json::from_str(manifest).map_err(|e| Error::Manifest(eyre::eyre!(e)))?
+    } else {
+        csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(manifest.as_bytes())
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .map_err(|e| Error::Manifest(eyre::eyre!(e)))?
+    };
+    if payments.is_empty() {
+        return Err(Error::Empty);
+    }
+    Ok(payments)
+}
+
+/// Transaction fields shared by every payment of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchTemplate {
+    /// EIP-155 chain ID.
+    pub chain_id: u64,
+    /// Nonce of the first payment; each following payment uses the next one.
+    pub start_nonce: u64,
+    /// Gas limit of payments that do not set their own.
+    pub gas_limit: u64,
+    /// Fee model and type-specific fields.
+    pub unique: UniquePayload,
+}
+
+impl BatchTemplate {
+    /// The unsigned transactions of `payments`, in manifest order with sequential nonces.
+    pub fn transactions(&self, payments: &[Payment]) -> Vec<UnsignedTx> {
+        payments
+            .iter()
+            .zip(self.start_nonce..)
+            .map(|(payment, nonce)| UnsignedTx {
+                common: CommonPayload {
+                    chain_id: self.chain_id,
+                    nonce,
+                    gas_limit: payment.gas_limit.unwrap_or(self.gas_limit),
+                    to: Some(payment.to),
+                    value: payment.value,
+                    input: payment.data.clone().unwrap_or_default(),
+                },
+                unique: self.unique.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Sign every payment with `signer`, returning signed envelopes in manifest order.
+///
+/// # Errors
+///
+/// Returns [`Error::Sign`] or [`Error::Envelope`] with the zero-based row index of the
+/// first payment that cannot be signed.
+pub async fn sign_batch<S>(
+    template: &BatchTemplate,
+    payments: &[Payment],
+    signer: &S,
+) -> Result<Vec<Envelope>, Error>
+where
+    S: SignerBackend + ?Sized,
+{
+    let mut envelopes = Vec::with_capacity(payments.len());
+    for (row, (payment, tx)) in payments.iter().zip(template.transactions(payments)).enumerate() {
+        let signed = tx.sign_async(signer).await.map_err(|e| Error::Sign(row, e))?;
+        let payload = Payload::Signed(signed.encode_2718());
+        let metadata = Metadata::new(payment.description.clone());
+        let envelope = Envelope::new(payload, Some(signer.address()), metadata)
+            .map_err(|e| Error::Envelope(row, e))?;
+        envelopes.push(envelope);
+    }
+    Ok(envelopes)
+}
+
+/// Totals of a batch, for review before the signed transactions leave the machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of transactions.
+    pub count: usize,
+    /// Nonce of the first transaction.
+    pub first_nonce: u64,
+    /// Nonce of the last transaction.
+    pub last_nonce: u64,
+    /// Sum of all transferred values.
+    pub total_value: Wei,
+    /// Sum of the maximum fees of all transactions.
+    pub max_fees: Wei,
+}
+
+impl Summary {
+    /// Sum up the values and maximum fees of `txs`.
+    pub fn new(txs: &[UnsignedTx]) -> Self {
+        let nonces = txs.iter().map(|tx| tx.common.nonce);
+        Self {
+            count: txs.len(),
+            first_nonce: nonces.clone().min().unwrap_or_default(),
+            last_nonce: nonces.max().unwrap_or_default(),
+            total_value: txs.iter().map(|tx| tx.common.value).sum(),
+            max_fees: txs.iter().map(UnsignedTx::max_fee).sum(),
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let eth = |wei: Wei| format!("{} ETH", inspect::trim_decimal(&format_ether(wei)));
+        writeln!(f, "Transactions:             {}", self.count)?;
+        writeln!(f, "Nonces:                   {} to {}", self.first_nonce, self.last_nonce)?;
+        writeln!(f, "Total value:              {}", eth(self.total_value))?;
+        writeln!(f, "Maximum fees:             {}", eth(self.max_fees))?;
+        writeln!(f, "Maximum total cost:       {}", eth(self.total_value + self.max_fees))
+    }
+}
+
+/// What [`submit_batch`] does after the node rejects a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Skip the sender's remaining transactions, since its later nonces cannot be mined after
+    /// a gap; other senders are unaffected.
+    Stop,
+    /// Submit the remaining transactions regardless.
+    Continue,
+}
+
+/// Outcome of submitting one transaction of a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// Accepted by the node.
+    Submitted,
+    /// Rejected by the node or not delivered, with the reason.
+    Failed(String),
+    /// Not sent because an earlier transaction of the same sender failed under
+    /// [`FailurePolicy::Stop`].
+    Skipped,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Submitted => write!(f, "submitted"),
+            Self::Failed(reason) => write!(f, "failed: {reason}"),
+            Self::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// One transaction of a batch submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Recovered sender.
+    pub sender: Address,
+    /// Transaction nonce.
+    pub nonce: u64,
+    /// Transaction hash, known before submission.
+    pub hash: TxHash,
+    /// What happened to the transaction.
+    pub status: Status,
+}
+
+/// Per-transaction results of [`submit_batch`], in submission order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report(pub Vec<Submission>);
+
+impl Report {
+    /// Number of transactions that were not submitted, failed or skipped.
+    pub fn unsubmitted(&self) -> usize {
+        self.0.iter().filter(|submission| submission.status != Status::Submitted).count()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<8} {:<42} {:<66} STATUS", "NONCE", "SENDER", "HASH")?;
+        for Submission { sender, nonce, hash, status } in &self.0 {
+            writeln!(f, "{nonce:<8} {sender:<42} {hash:<66} {status}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Submit signed transactions in nonce order (per sender), following `policy` on failure.
+///
+/// Node rejections do not fail the call; they are recorded in the returned [`Report`].
+///
+/// # Errors
+///
+/// Returns [`Error::Invalid`] if a transaction's sender cannot be recovered and
+/// [`Error::DuplicateNonce`] if a sender uses a nonce twice; nothing is submitted then.
+pub async fn submit_batch(
+    txs: Vec<AnyTxSigned>,
+    rpc_url: url::Url,
+    policy: FailurePolicy,
+) -> Result<Report, Error> {
+    let mut txs = txs
+        .into_iter()
+        .enumerate()
+        .map(|(index, tx)| {
+            let inspection = tx.inspect().map_err(|e| Error::Invalid(index, eyre::eyre!(e)))?;
+            Ok((inspection.sender, inspection.nonce, inspection.hash, tx))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    txs.sort_by_key(|(sender, nonce, ..)| (*sender, *nonce));
+    if let Some(pair) = txs.windows(2).find(|pair| (pair[0].0, pair[0].1) == (pair[1].0, pair[1].1))
+    {
+        return Err(Error::DuplicateNonce(pair[0].0, pair[0].1));
+    }
+
+    let mut submissions = Vec::with_capacity(txs.len());
+    let mut stopped = HashSet::new();
+    for (sender, nonce, hash, tx) in txs {
+        let status = if stopped.contains(&sender) {
+            Status::Skipped
+        } else {
+            match tx.submit(rpc_url.clone()).await {
+                Ok(_) => Status::Submitted,
+                Err(e) => {
+                    if policy == FailurePolicy::Stop {
+                        stopped.insert(sender);
+                    }
+                    Status::Failed(e.to_string())
+                }
+            }
+        };
+        submissions.push(Submission { sender, nonce, hash, status });
+    }
+    Ok(Report(submissions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LegacyPayload;
+    use alloy::primitives::{U256, address, bytes};
+    use alloy::signers::local::PrivateKeySigner;
+
+    const ALICE: Address = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+    const BOB: Address = address!("0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC");
+
+    fn template() -> BatchTemplate {
+        BatchTemplate {
+            chain_id: 11155111,
+            start_nonce: 7,
+            gas_limit: 21_000,
+            unique: UniquePayload::Legacy(LegacyPayload { gas_price: 10_000_000_000 }),
+        }
+    }
+
+    #[test]
+    fn csv_and_json_manifests_agree() -> eyre::Result<()> {
+        let csv = format!(
+            "to,eth,data,gasLimit,description\n\
+             {ALICE},0.5,,,\"Payroll, March\"\n\
+             {BOB}, 1.25 ,0xa9059cbb,60000,\n"
+        );
+        let json = format!(
+            r#"[{{"to": "{ALICE}", "eth": "0.5", "description": "Payroll, March"}},
+                {{"to": "{BOB}", "eth": "1.25", "data": "0xa9059cbb", "gasLimit": 60000}}]"#
+        );
+        let payments = parse_manifest(&csv)?;
+        assert_eq!(payments, parse_manifest(&json)?);
+        assert_eq!(payments[0].value, U256::from(500_000_000_000_000_000_u64));
+        assert_eq!(payments[0].description.as_deref(), Some("Payroll, March"));
+        assert_eq!(payments[1].data, Some(bytes!("a9059cbb")));
+
+        assert!(matches!(parse_manifest("to,eth\n"), Err(Error::Empty)));
+        assert!(matches!(
+            parse_manifest(&format!("to,eth\n{ALICE},lots\n")),
+            Err(Error::Manifest(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn signs_rows_with_sequential_nonces() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let payments = parse_manifest(&format!("to,eth,gasLimit\n{ALICE},0.5,\n{BOB},1,30000\n"))?;
+        let envelopes = sign_batch(&template(), &payments, &signer).await?;
+        assert_eq!(envelopes.len(), 2);
+        for (envelope, (nonce, to)) in envelopes.into_iter().zip([(7, ALICE), (8, BOB)]) {
+            let inspection = envelope.into_signed()?.inspect()?;
+            assert_eq!(inspection.nonce, nonce);
+            assert_eq!(inspection.to, to.into());
+            assert_eq!(inspection.sender, signer.address());
+        }
+
+        let summary = Summary::new(&template().transactions(&payments));
+        assert_eq!((summary.count, summary.first_nonce, summary.last_nonce), (2, 7, 8));
+        assert_eq!(summary.max_fees, U256::from((21_000 + 30_000) * 10_000_000_000_u64));
+        assert!(summary.to_string().contains("Total value:              1.5 ETH"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn submission_follows_nonce_order_and_failure_policy() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let payments = parse_manifest(&format!("to,eth\n{ALICE},1\n{BOB},2\n{ALICE},3\n"))?;
+        let signed = || async {
+            let mut txs = Vec::new();
+            for envelope in sign_batch(&template(), &payments, &signer).await? {
+                txs.push(envelope.into_signed()?);
+            }
+            txs.reverse();
+            eyre::Ok(txs)
+        };
+        // Nothing listens on port 1, so every submission fails.
+        let rpc_url: url::Url = "http://127.0.0.1:1".parse()?;
+
+        let report = submit_batch(signed().await?, rpc_url.clone(), FailurePolicy::Stop).await?;
+        let nonces: Vec<_> = report.0.iter().map(|submission| submission.nonce).collect();
+        assert_eq!(nonces, [7, 8, 9]);
+        assert!(matches!(report.0[0].status, Status::Failed(_)));
+        assert_eq!(report.0[1].status, Status::Skipped);
+        assert_eq!(report.unsubmitted(), 3);
+
+        let report =
+            submit_batch(signed().await?, rpc_url.clone(), FailurePolicy::Continue).await?;
+        assert!(report.0.iter().all(|submission| matches!(submission.status, Status::Failed(_))));
+
+        let mut txs = signed().await?;
+        txs.extend(signed().await?);
+        let result = submit_batch(txs, rpc_url, FailurePolicy::Continue).await;
+        assert!(
+            matches!(result, Err(Error::DuplicateNonce(sender, 7)) if sender == signer.address())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stop_only_skips_the_failing_sender() -> eyre::Result<()> {
+        let (alice, bob) = (PrivateKeySigner::random(), PrivateKeySigner::random());
+        let payments = parse_manifest(&format!("to,eth\n{ALICE},1\n{BOB},2\n"))?;
+        let mut txs = Vec::new();
+        for signer in [&alice, &bob] {
+            for envelope in sign_batch(&template(), &payments, signer).await? {
+                txs.push(envelope.into_signed()?);
+            }
+        }
+        let rejected = txs[0].inspect()?.hash;
+        let rpc_url = crate::confirm::tests::mock_rpc(move |method, params| {
+            let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap_or_default();
+            match alloy::primitives::keccak256(&raw) {
+                _ if method != "eth_sendRawTransaction" => serde_json::Value::Null,
+                hash if hash == rejected => {
+                    serde_json::json!({"error": {"code": -32000, "message": "nonce too low"}})
+                }
+                hash => serde_json::json!(hash),
+            }
+        });
+
+        let report = submit_batch(txs, rpc_url, FailurePolicy::Stop).await?;
+        let statuses = |sender: Address| {
+            let submissions = report.0.iter().filter(move |s| s.sender == sender);
+            submissions.map(|s| s.status.clone()).collect::<Vec<_>>()
+        };
+        assert!(matches!(statuses(alice.address())[..], [Status::Failed(_), Status::Skipped]));
+        assert_eq!(statuses(bob.address()), [Status::Submitted, Status::Submitted]);
+        assert_eq!(report.unsubmitted(), 2);
+        Ok(())
+    }
+}
@@ -1,14 +1,44 @@
+use std::time::Duration;
+
 use super::*;
+use alloy::consensus::Transaction;
 use alloy::network::Ethereum;
-use alloy::primitives::TxHash;
-use alloy::providers::{PendingTransactionBuilder, RootProvider};
+use alloy::primitives::{Address, B256, TxHash};
+use alloy::providers::{PendingTransactionBuilder, Provider, RootProvider};
 use alloy::rpc::types::eth;
 
+/// Consecutive polls a transaction must stay missing, or its nonce taken, before it is reported
+/// as dropped or replaced, so a lagging node behind a load balancer cannot cause a false report.
+pub const DROPPED_AFTER_POLLS: u32 = 3;
+
 /// Errors for the first transaction confirmation.
 #[derive(Display, Error, Debug)]
 pub enum Error {
     /// Failed to retrieve the transaction receipt from the RPC endpoint: {0}
     Receipt(eyre::Report),
+    /// Timed out after {1:?} waiting for transaction {0} to be confirmed
+    Timeout(TxHash, Duration),
+}
+
+/// How deep and how long to wait for a transaction to be confirmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitOptions {
+    /// Blocks that must include or follow the receipt's block; 1 accepts the first inclusion.
+    pub confirmations: u64,
+    /// Give up after this long; `None` waits indefinitely.
+    pub timeout: Option<Duration>,
+    /// Delay between RPC polls.
+    pub poll_interval: Duration,
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            confirmations: 1,
+            timeout: Some(Duration::from_secs(300)),
+            poll_interval: Duration::from_secs(2),
+        }
+    }
 }
 
 /// Retrieve the transaction receipt for a given hash from a JSON-RPC endpoint.
@@ -25,3 +55,489 @@ pub async fn get_receipt(
     let receipt = pending.get_receipt().await.map_err(|e| Error::Receipt(eyre::eyre!(e)))?;
     Ok(receipt)
 }
+
+/// Final state of a transaction tracked to the requested depth.
+#[derive(Debug, Clone)]
+pub enum Confirmation {
+    /// Included in the canonical chain in the first block it was seen in, at the requested depth.
+    Confirmed(eth::TransactionReceipt),
+    /// Removed from its first block by a reorg, then included in another block and confirmed there.
+    Reincluded {
+        /// Receipt from the block that finally confirmed the transaction.
+        receipt: eth::TransactionReceipt,
+        /// Hash of the block that first included the transaction.
+        orphaned_block: B256,
+    },
+    /// Removed from the canonical chain by a reorg and no longer known to the node.
+    Dropped {
+        /// Hash of the block that included the transaction before the reorg.
+        orphaned_block: B256,
+    },
+    /// Never mined: another transaction of the sender used its nonce, such as one signed with
+    /// `sign replace` or `sign cancel`.
+    Replaced {
+        /// Account that signed the transaction.
+        sender: Address,
+        /// Nonce now used by another transaction.
+        nonce: u64,
+    },
+}
+
+impl Confirmation {
+    /// The receipt of the confirmed transaction, unless it was dropped or replaced.
+    pub fn receipt(&self) -> Option<&eth::TransactionReceipt> {
+        match self {
+            Self::Confirmed(receipt) | Self::Reincluded { receipt, .. } => Some(receipt),
+            Self::Dropped { .. } | Self::Replaced { .. } => None,
+        }
+    }
+}
+
+/// How waiting for a transaction ended, for callers that branch on the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    /// Confirmed with a successful receipt.
+    Success,
+    /// Confirmed, but the execution reverted.
+    Reverted,
+    /// Not confirmed before the timeout.
+    TimedOut,
+    /// Dropped after a reorg or replaced by another transaction with the same nonce.
+    Dropped,
+    /// The RPC endpoint failed or returned invalid data.
+    RpcError,
+}
+
+impl Outcome {
+    /// Outcome of a finished confirmation.
+    pub fn of(confirmation: &Confirmation) -> Self {
+        match confirmation.receipt() {
+            Some(receipt) if receipt.status() => Self::Success,
+            Some(_) => Self::Reverted,
+            None => Self::Dropped,
+        }
+    }
+
+    /// Outcome of a confirmation that failed with `error`.
+    pub fn of_error(error: &Error) -> Self {
+        match error {
+            Error::Receipt(_) => Self::RpcError,
+            Error::Timeout(..) => Self::TimedOut,
+        }
+    }
+
+    /// Process exit code for this outcome, skipping 1 and 2 which mean a generic error and a
+    /// command-line usage error.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::Reverted => 3,
+            Self::TimedOut => 4,
+            Self::Dropped => 5,
+            Self::RpcError => 6,
+        }
+    }
+}
+
+/// Poll a JSON-RPC endpoint until the receipt's block is `options.confirmations` deep.
+///
+/// Until then, every poll checks that the receipt's block is still canonical, so a receipt
+/// orphaned by a reorg is never reported as confirmed: the transaction either shows up in
+/// another block ([`Confirmation::Reincluded`]), returns to the mempool and keeps being
+/// tracked, or disappears from the node ([`Confirmation::Dropped`]). A pending transaction
+/// whose nonce gets used by another one is [`Confirmation::Replaced`]. Either is only reported
+/// after [`DROPPED_AFTER_POLLS`] polls in a row, and a drop only once the chain head has
+/// moved past the orphaned block.
+///
+/// # Errors
+///
+/// Returns [`Error::Receipt`] if an RPC call fails and [`Error::Timeout`] if the
+/// transaction is not confirmed within `options.timeout`.
+pub async fn wait_for_confirmations(
+    tx_hash: TxHash,
+    rpc_url: url::Url,
+    options: &WaitOptions,
+) -> Result<Confirmation, Error> {
+    let provider = RootProvider::<Ethereum>::new_http(rpc_url);
+    let wait = poll_confirmations(&provider, tx_hash, options);
+    match options.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| Error::Timeout(tx_hash, timeout))?,
+        None => wait.await,
+    }
+}
+
+/// Poll for the receipt, its block and the chain head until the receipt is deep enough.
+async fn poll_confirmations(
+    provider: &RootProvider<Ethereum>,
+    tx_hash: TxHash,
+    options: &WaitOptions,
+) -> Result<Confirmation, Error> {
+    let rpc = |e: alloy::transports::TransportError| Error::Receipt(eyre::eyre!(e));
+    // Hash and number of the block that first included the transaction, kept to detect a reorg
+    // moving it.
+    let mut first_block = None;
+    // Sender and nonce, recorded from the node as soon as it knows the transaction.
+    let mut origin = None;
+    // Consecutive polls in which the transaction was unknown after being included, or its nonce
+    // was used by another transaction.
+    let mut missing = 0;
+    loop {
+        match provider.get_transaction_receipt(tx_hash).await.map_err(rpc)? {
+            Some(receipt) => {
+                missing = 0;
+                if let (Some(number), Some(hash)) = (receipt.block_number, receipt.block_hash) {
+                    // Nodes may briefly serve receipts from orphaned blocks; only count canonical ones.
+                    let canonical = provider
+                        .get_block_by_number(number.into())
+                        .await
+                        .map_err(rpc)?
+                        .is_some_and(|block| block.header.hash == hash);
+                    if canonical {
+                        let (first, _) = *first_block.get_or_insert((hash, number));
+                        let head = provider.get_block_number().await.map_err(rpc)?;
+                        if head.saturating_sub(number) + 1 >= options.confirmations {
+                            return Ok(if first == hash {
+                                Confirmation::Confirmed(receipt)
+                            } else {
+                                Confirmation::Reincluded { receipt, orphaned_block: first }
+                            });
+                        }
+                    }
+                }
+            }
+            None => {
+                let known = provider.get_transaction_by_hash(tx_hash).await.map_err(rpc)?;
+                if let Some(tx) = &known {
+                    origin.get_or_insert((tx.inner.signer(), tx.nonce()));
+                }
+                let replaced = match origin {
+                    Some((sender, nonce)) => {
+                        provider.get_transaction_count(sender).latest().await.map_err(rpc)? > nonce
+                    }
+                    None => false,
+                };
+                let orphaned = first_block.is_some() && known.is_none();
+                missing = if replaced || orphaned { missing + 1 } else { 0 };
+                if missing >= DROPPED_AFTER_POLLS {
+                    match (first_block, origin) {
+                        (Some((orphaned_block, number)), _) => {
+                            if provider.get_block_number().await.map_err(rpc)? > number {
+                                return Ok(Confirmation::Dropped { orphaned_block });
+                            }
+                        }
+                        (None, Some((sender, nonce))) => {
+                            return Ok(Confirmation::Replaced { sender, nonce });
+                        }
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+        tokio::time::sleep(options.poll_interval).await;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+    use serde_json::{Value, json};
+
+    /// Serve JSON-RPC over HTTP on a local port, answering each call with `handler(method, params)`.
+    ///
+    /// A returned `{"error": ...}` object is sent as the JSON-RPC error instead of a result.
+    pub(crate) fn mock_rpc(handler: impl Fn(&str, &Value) -> Value + Send + 'static) -> url::Url {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock RPC");
+        let url = format!("http://{}", listener.local_addr().expect("local address"));
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(&stream);
+                let mut content_length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap_or_default();
+                        }
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; content_length];
+                if reader.read_exact(&mut body).is_err() {
+                    continue;
+                }
+                let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+                let method = request["method"].as_str().unwrap_or_default();
+                let mut response = json!({"jsonrpc": "2.0", "id": request["id"]});
+                match handler(method, &request["params"]) {
+                    Value::Object(error) if error.contains_key("error") => {
+                        response["error"] = error["error"].clone();
+                    }
+                    result => response["result"] = result,
+                }
+                let response = response.to_string();
+                let _ = write!(
+                    &stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                );
+            }
+        });
+        url.parse().expect("mock RPC URL")
+    }
+
+    pub(crate) fn quantity(number: u64) -> Value {
+        json!(format!("{number:#x}"))
+    }
+
+    /// Minimal successful receipt of `tx_hash` mined in block `number` with hash `block_hash`.
+    pub(crate) fn receipt(tx_hash: TxHash, number: u64, block_hash: B256) -> Value {
+        json!({
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockHash": block_hash,
+            "blockNumber": quantity(number),
+            "from": "0x0000000000000000000000000000000000000001",
+            "to": "0x0000000000000000000000000000000000000002",
+            "contractAddress": null,
+            "gasUsed": "0x5208",
+            "cumulativeGasUsed": "0x5208",
+            "effectiveGasPrice": "0x3b9aca00",
+            "status": "0x1",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "type": "0x2"
+        })
+    }
+
+    /// Minimal block header with the given number and hash.
+    fn block(number: u64, hash: B256) -> Value {
+        json!({
+            "hash": hash,
+            "parentHash": B256::ZERO,
+            "sha3Uncles": B256::ZERO,
+            "miner": "0x0000000000000000000000000000000000000000",
+            "stateRoot": B256::ZERO,
+            "transactionsRoot": B256::ZERO,
+            "receiptsRoot": B256::ZERO,
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "difficulty": "0x0",
+            "number": quantity(number),
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x5208",
+            "timestamp": "0x0",
+            "extraData": "0x",
+            "mixHash": B256::ZERO,
+            "nonce": "0x0000000000000000",
+            "uncles": [],
+            "transactions": []
+        })
+    }
+
+    /// Pending legacy transaction `tx_hash` from `0x…01` with the given nonce.
+    fn pending_transaction(tx_hash: TxHash, nonce: u64) -> Value {
+        json!({
+            "type": "0x0",
+            "hash": tx_hash,
+            "nonce": quantity(nonce),
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null,
+            "from": "0x0000000000000000000000000000000000000001",
+            "to": "0x0000000000000000000000000000000000000002",
+            "value": "0x0",
+            "gasPrice": "0x3b9aca00",
+            "gas": "0x5208",
+            "input": "0x",
+            "chainId": "0x1",
+            "v": "0x25",
+            "r": "0x1",
+            "s": "0x1"
+        })
+    }
+
+    /// Number requested by an `eth_getBlockByNumber` call.
+    fn requested_number(params: &Value) -> u64 {
+        let number = params[0].as_str().unwrap_or_default().trim_start_matches("0x");
+        u64::from_str_radix(number, 16).unwrap_or_default()
+    }
+
+    fn fast(confirmations: u64, timeout_ms: u64) -> WaitOptions {
+        WaitOptions {
+            confirmations,
+            timeout: Some(Duration::from_millis(timeout_ms)),
+            poll_interval: Duration::from_millis(10),
+        }
+    }
+
+    #[tokio::test]
+    async fn waits_until_receipt_is_deep_enough() -> eyre::Result<()> {
+        let tx_hash = TxHash::repeat_byte(0x11);
+        let block_hash = B256::repeat_byte(0xaa);
+        let head = Arc::new(AtomicU64::new(100));
+        let polled = head.clone();
+        let rpc_url = mock_rpc(move |method, params| match method {
+            "eth_getTransactionReceipt" => receipt(tx_hash, 100, block_hash),
+            "eth_getBlockByNumber" => block(requested_number(params), block_hash),
+            // Each poll of the chain head sees one more block.
+            "eth_blockNumber" => quantity(polled.fetch_add(1, Ordering::SeqCst)),
+            _ => Value::Null,
+        });
+
+        let confirmation = wait_for_confirmations(tx_hash, rpc_url, &fast(3, 5_000)).await?;
+        assert!(matches!(&confirmation, Confirmation::Confirmed(r) if r.block_number == Some(100)));
+        assert_eq!(head.load(Ordering::SeqCst), 103);
+        Ok(())
+    }
+
+    #[test]
+    fn outcome_follows_receipt_status() -> eyre::Result<()> {
+        let tx_hash = TxHash::repeat_byte(0x55);
+        let orphaned_block = B256::repeat_byte(0xaa);
+        let mut json = receipt(tx_hash, 100, orphaned_block);
+        let success: eth::TransactionReceipt = serde_json::from_value(json.clone())?;
+        json["status"] = json!("0x0");
+        let reverted: eth::TransactionReceipt = serde_json::from_value(json)?;
+
+        assert_eq!(Outcome::of(&Confirmation::Confirmed(success.clone())), Outcome::Success);
+        let reincluded = Confirmation::Reincluded { receipt: success, orphaned_block };
+        assert_eq!(Outcome::of(&reincluded), Outcome::Success);
+        assert_eq!(Outcome::of(&Confirmation::Confirmed(reverted)), Outcome::Reverted);
+        assert_eq!(Outcome::of(&Confirmation::Dropped { orphaned_block }), Outcome::Dropped);
+        let replaced = Confirmation::Replaced { sender: Address::ZERO, nonce: 5 };
+        assert_eq!(Outcome::of(&replaced).exit_code(), 5);
+
+        let timeout = Error::Timeout(tx_hash, Duration::from_secs(1));
+        assert_eq!(Outcome::of_error(&timeout).exit_code(), 4);
+        assert_eq!(Outcome::of_error(&Error::Receipt(eyre::eyre!("down"))).exit_code(), 6);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn times_out_while_pending() {
+        let tx_hash = TxHash::repeat_byte(0x22);
+        let rpc_url = mock_rpc(|_, _| Value::Null);
+        let result = wait_for_confirmations(tx_hash, rpc_url, &fast(1, 100)).await;
+        assert!(matches!(result, Err(Error::Timeout(hash, _)) if hash == tx_hash));
+    }
+
+    #[tokio::test]
+    async fn reports_reinclusion_after_reorg() -> eyre::Result<()> {
+        let tx_hash = TxHash::repeat_byte(0x33);
+        let (orphaned, canonical) = (B256::repeat_byte(0xaa), B256::repeat_byte(0xcc));
+        let head = Arc::new(AtomicU64::new(100));
+        let rpc_url = mock_rpc(move |method, params| {
+            // From block 102 on, a reorg has replaced block 100 and moved the transaction to 101.
+            let reorged = head.load(Ordering::SeqCst) >= 102;
+            match method {
+                "eth_getTransactionReceipt" if reorged => receipt(tx_hash, 101, canonical),
+                "eth_getTransactionReceipt" => receipt(tx_hash, 100, orphaned),
+                "eth_getBlockByNumber" => {
+                    let number = requested_number(params);
+                    let hash = if reorged { canonical } else { orphaned };
+                    block(number, if number == 100 && reorged { B256::ZERO } else { hash })
+                }
+                "eth_blockNumber" => quantity(head.fetch_add(1, Ordering::SeqCst)),
+                _ => Value::Null,
+            }
+        });
+
+        match wait_for_confirmations(tx_hash, rpc_url, &fast(5, 5_000)).await? {
+            Confirmation::Reincluded { receipt, orphaned_block } => {
+                assert_eq!(orphaned_block, orphaned);
+                assert_eq!(
+                    (receipt.block_number, receipt.block_hash),
+                    (Some(101), Some(canonical))
+                );
+            }
+            other => panic!("expected a re-inclusion, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reports_transaction_dropped_by_reorg() -> eyre::Result<()> {
+        let tx_hash = TxHash::repeat_byte(0x44);
+        let block_hash = B256::repeat_byte(0xaa);
+        let polls = Arc::new(AtomicU64::new(0));
+        let head = Arc::new(AtomicU64::new(100));
+        let rpc_url = mock_rpc(move |method, params| match method {
+            // The receipt is seen once, then the reorg removes the transaction entirely.
+            "eth_getTransactionReceipt" if polls.fetch_add(1, Ordering::SeqCst) == 0 => {
+                receipt(tx_hash, 100, block_hash)
+            }
+            "eth_getBlockByNumber" => block(requested_number(params), block_hash),
+            "eth_blockNumber" => quantity(head.fetch_add(1, Ordering::SeqCst)),
+            _ => Value::Null,
+        });
+
+        let confirmation = wait_for_confirmations(tx_hash, rpc_url, &fast(5, 5_000)).await?;
+        assert!(
+            matches!(confirmation, Confirmation::Dropped { orphaned_block } if orphaned_block == block_hash)
+        );
+        assert!(confirmation.receipt().is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ignores_a_lagging_node_missing_the_transaction() -> eyre::Result<()> {
+        let tx_hash = TxHash::repeat_byte(0x45);
+        let block_hash = B256::repeat_byte(0xaa);
+        let polls = Arc::new(AtomicU64::new(0));
+        let head = Arc::new(AtomicU64::new(100));
+        let rpc_url = mock_rpc(move |method, params| match method {
+            // A lagging backend answers the second poll without the receipt or the transaction.
+            "eth_getTransactionReceipt" if polls.fetch_add(1, Ordering::SeqCst) == 1 => Value::Null,
+            "eth_getTransactionReceipt" => receipt(tx_hash, 100, block_hash),
+            "eth_getBlockByNumber" => block(requested_number(params), block_hash),
+            "eth_blockNumber" => quantity(head.fetch_add(1, Ordering::SeqCst)),
+            _ => Value::Null,
+        });
+
+        let confirmation = wait_for_confirmations(tx_hash, rpc_url, &fast(3, 5_000)).await?;
+        assert!(matches!(confirmation, Confirmation::Confirmed(_)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reports_transaction_replaced_in_mempool() -> eyre::Result<()> {
+        let tx_hash = TxHash::repeat_byte(0x47);
+        let polls = Arc::new(AtomicU64::new(0));
+        let rpc_url = mock_rpc(move |method, params| match method {
+            // The node knows the pending transaction once; then a replacement is mined.
+            "eth_getTransactionByHash" if polls.fetch_add(1, Ordering::SeqCst) == 0 => {
+                pending_transaction(tx_hash, 5)
+            }
+            "eth_getTransactionCount" if params[1] == "latest" => quantity(6),
+            "eth_blockNumber" => quantity(100),
+            _ => Value::Null,
+        });
+
+        let confirmation = wait_for_confirmations(tx_hash, rpc_url, &fast(1, 5_000)).await?;
+        let sender = Address::with_last_byte(1);
+        assert!(
+            matches!(confirmation, Confirmation::Replaced { sender: s, nonce: 5 } if s == sender)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_waiting_while_the_nonce_is_free() {
+        let tx_hash = TxHash::repeat_byte(0x48);
+        let rpc_url = mock_rpc(move |method, _| match method {
+            "eth_getTransactionByHash" => pending_transaction(tx_hash, 5),
+            "eth_getTransactionCount" => quantity(5),
+            _ => Value::Null,
+        });
+        let result = wait_for_confirmations(tx_hash, rpc_url, &fast(1, 200)).await;
+        assert!(matches!(result, Err(Error::Timeout(..))));
+    }
+}
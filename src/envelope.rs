@@ -0,0 +1,277 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::*;
+use alloy::primitives::{Address, B256, keccak256};
+use serde::{Deserialize, Serialize};
+use sign::UnsignedTx;
+
+/// Current version of the interchange format; bumped on incompatible changes.
+pub const VERSION: u32 = 1;
+
+/// Errors for reading transaction interchange envelopes.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to parse the envelope JSON: {0}
+    Parse(eyre::Report),
+    /// Unsupported envelope version {0}; this build reads version 1
+    UnsupportedVersion(u32),
+    /// Checksum mismatch: the envelope says {0} but its contents hash to {1}; it was altered or corrupted
+    Checksum(B256, B256),
+    /// Expected a {0} transaction envelope
+    WrongKind(&'static str),
+    /// Failed to decode the signed transaction: {0}
+    Decode(eyre::Report),
+    /// The transaction is signed by {0}, but the envelope expects {1}
+    SenderMismatch(Address, Address),
+    /// Failed to serialize the envelope: {0}
+    Serialize(eyre::Report),
+}
+
+/// Human-readable context travelling with a transaction between machines.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    /// Free-form statement of intent, e.g. "Pay invoice #42".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Tool and version that wrote the envelope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    /// Creation time in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+}
+
+impl Metadata {
+    /// Metadata stamped with this tool's name and version and the current time.
+    pub fn new(description: Option<String>) -> Self {
+        Self {
+            description,
+            created_by: Some(concat!("eth-offline-signer ", env!("CARGO_PKG_VERSION")).to_owned()),
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()),
+        }
+    }
+}
+
+/// The transaction carried by an envelope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Payload {
+    /// A transaction request waiting to be signed.
+    Unsigned(Box<UnsignedTx>),
+    /// A signed transaction in its EIP-2718 encoding, ready to submit.
+    Signed(TxEip2718Bytes<AnyTxSigned>),
+}
+
+/// Versioned, self-describing container for moving transactions between machines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Envelope {
+    /// Interchange format version, see [`VERSION`].
+    pub version: u32,
+    /// Account expected to sign the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<Address>,
+    /// Human-readable context.
+    #[serde(default)]
+    pub metadata: Metadata,
+    /// The unsigned or signed transaction.
+    pub payload: Payload,
+    /// Keccak-256 of the envelope serialized without this field, guarding against corruption.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<B256>,
+}
+
+impl Envelope {
+    /// Wrap `payload` in a current-version envelope and compute its checksum.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Serialize`] if the envelope cannot be serialized for the checksum.
+    pub fn new(
+        payload: Payload,
+        sender: Option<Address>,
+        metadata: Metadata,
+    ) -> Result<Self, Error> {
+        let mut envelope = Self { version: VERSION, sender, metadata, payload, checksum: None };
+        envelope.checksum = Some(envelope.compute_checksum()?);
+        Ok(envelope)
+    }
+
+    /// Keccak-256 of the compact JSON serialization of this envelope without its checksum.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Serialize`] if the envelope cannot be serialized.
+    pub fn compute_checksum(&self) -> Result<B256, Error> {
+        let unsealed = Self { checksum: None, ..self.clone() };
+        let json = serde_json::to_vec(&unsealed).map_err(|e| Error::Serialize(eyre::eyre!(e)))?;
+        Ok(keccak256(json))
+    }
+
+    /// Parse an envelope and verify its version and checksum.
+    ///
+    /// An envelope without a checksum is accepted, so hand-written files stay usable.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Parse`] for malformed JSON, [`Error::UnsupportedVersion`] for
+    /// envelopes from an incompatible release, and [`Error::Checksum`] if the contents
+    /// do not match the recorded checksum.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str::<Self>(json).map_err(|e| Error::Parse(eyre::eyre!(e)))?.verify()
+    }
+
+    /// Parse a JSON array of envelopes, such as the output of batch signing, verifying each.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Envelope::from_json`] for the first invalid envelope.
+    pub fn list_from_json(json: &str) -> Result<Vec<Self>, Error> {
+        serde_json::from_str::<Vec<Self>>(json)
+            .map_err(|e| Error::Parse(eyre::eyre!(e)))?
+            .into_iter()
+            .map(Self::verify)
+            .collect()
+    }
+
+    /// Check the version and, if present, the checksum of a parsed envelope.
+    fn verify(self) -> Result<Self, Error> {
+        if self.version != VERSION {
+            return Err(Error::UnsupportedVersion(self.version));
+        }
+        if let Some(checksum) = self.checksum {
+            let computed = self.compute_checksum()?;
+            if checksum != computed {
+                return Err(Error::Checksum(checksum, computed));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Serialize this envelope as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Serialize`] if the envelope cannot be serialized.
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::Serialize(eyre::eyre!(e)))
+    }
+
+    /// Take the unsigned transaction out of this envelope.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WrongKind`] if the envelope holds a signed transaction.
+    pub fn into_unsigned(self) -> Result<UnsignedTx, Error> {
+        let Payload::Unsigned(tx) = self.payload else {
+            return Err(Error::WrongKind("unsigned"));
+        };
+        Ok(*tx)
+    }
+
+    /// Decode the signed transaction in this envelope, checking its sender if one is expected.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WrongKind`] if the envelope holds an unsigned transaction,
+    /// [`Error::Decode`] if the bytes or signature are invalid, and
+    /// [`Error::SenderMismatch`] if the signer differs from [`Envelope::sender`].
+    pub fn into_signed(self) -> Result<AnyTxSigned, Error> {
+        let Payload::Signed(bytes) = self.payload else {
+            return Err(Error::WrongKind("signed"));
+        };
+        let signed =
+            AnyTxSigned::decode_2718(bytes.as_ref()).map_err(|e| Error::Decode(eyre::eyre!(e)))?;
+        if let Some(expected) = self.sender {
+            let sender = signed.inspect().map_err(|e| Error::Decode(eyre::eyre!(e)))?.sender;
+            if sender != expected {
+                return Err(Error::SenderMismatch(sender, expected));
+            }
+        }
+        Ok(signed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommonPayload, Eip1559Payload, LegacyPayload, UniquePayload};
+    use alloy::primitives::{Bytes, U256};
+    use alloy::signers::local::PrivateKeySigner;
+
+    fn unsigned_tx() -> UnsignedTx {
+        UnsignedTx {
+            common: CommonPayload {
+                chain_id: 11155111,
+                nonce: 0,
+                gas_limit: 21_000,
+                to: Some(Address::repeat_byte(0x02)),
+                value: U256::from(1),
+                input: Bytes::new(),
+            },
+            unique: UniquePayload::Legacy(LegacyPayload { gas_price: 20_000_000_000 }),
+        }
+    }
+
+    #[test]
+    fn unsigned_envelope_round_trips_through_json() -> eyre::Result<()> {
+        let tx = UnsignedTx {
+            unique: UniquePayload::Eip1559(Eip1559Payload {
+                max_fee_per_gas: 20_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                access_list: Default::default(),
+            }),
+            ..unsigned_tx()
+        };
+        let sender = Address::repeat_byte(0x01);
+        let envelope =
+            Envelope::new(Payload::Unsigned(Box::new(tx)), Some(sender), Metadata::default())?;
+        let json = serde_json::to_string(&envelope)?;
+        assert!(json.starts_with(r#"{"version":1,"sender":"#));
+        assert!(json.contains(r#""unique":{"eip1559":{"maxFeePerGas":20000000000"#));
+
+        let decoded = Envelope::from_json(&json)?;
+        assert_eq!(decoded.sender, Some(sender));
+        assert_eq!(decoded.checksum, envelope.checksum);
+        let tx = decoded.into_unsigned()?;
+        assert_eq!(tx.common.chain_id, 11155111);
+        assert!(matches!(tx.unique, UniquePayload::Eip1559(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn detects_tampering_through_checksum() -> eyre::Result<()> {
+        let metadata = Metadata::new(Some("Pay invoice #42".to_owned()));
+        let envelope =
+            Envelope::new(Payload::Unsigned(Box::new(unsigned_tx())), None, metadata.clone())?;
+        let json = envelope.to_json()?;
+        let decoded = Envelope::from_json(&json)?;
+        assert_eq!(decoded.metadata, metadata);
+        assert!(decoded.into_unsigned().is_ok());
+
+        let tampered = json.replace(r#""gasPrice": 20000000000"#, r#""gasPrice": 90000000000"#);
+        assert_ne!(tampered, json);
+        assert!(matches!(Envelope::from_json(&tampered), Err(Error::Checksum(..))));
+
+        let future = json.replace(r#""version": 1"#, r#""version": 2"#);
+        assert!(matches!(Envelope::from_json(&future), Err(Error::UnsupportedVersion(2))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn signed_envelope_checks_expected_sender() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let signed = unsigned_tx().sign_async(&signer).await?.encode_2718();
+
+        let envelope =
+            Envelope::new(Payload::Signed(signed), Some(signer.address()), Metadata::default())?;
+        let decoded = Envelope::from_json(&envelope.to_json()?)?;
+        assert!(matches!(decoded.clone().into_unsigned(), Err(Error::WrongKind("unsigned"))));
+        assert_eq!(decoded.clone().into_signed()?.inspect()?.sender, signer.address());
+
+        let impostor = Envelope { sender: Some(Address::repeat_byte(0x99)), ..decoded };
+        assert!(matches!(impostor.into_signed(), Err(Error::SenderMismatch(..))));
+        Ok(())
+    }
+}
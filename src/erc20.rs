@@ -0,0 +1,114 @@
+use super::*;
+use alloy::primitives::utils::{ParseUnits, format_units, parse_units};
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+
+sol! {
+    /// The subset of the ERC-20 interface used for offline token operations.
+    interface IERC20 {
+        function transfer(address to, uint256 amount) external returns (bool);
+        function approve(address spender, uint256 amount) external returns (bool);
+    }
+}
+
+/// Errors for ERC-20 call construction.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Invalid token amount `{0}`: {1}
+    Amount(String, eyre::Report),
+}
+
+/// A token operation encoded as calldata to the ERC-20 contract.
+#[derive(Debug, Clone)]
+pub enum Erc20Call {
+    /// Move `amount` base units from the signer to `recipient`.
+    Transfer {
+        /// Address receiving the tokens.
+        recipient: Address,
+        /// Amount in the token's base units.
+        amount: U256,
+    },
+    /// Allow `spender` to move up to `amount` base units on behalf of the signer.
+    Approve {
+        /// Address granted the allowance.
+        spender: Address,
+        /// Allowance in the token's base units.
+        amount: U256,
+    },
+}
+
+impl Erc20Call {
+    /// ABI-encoded calldata for this call.
+    pub fn calldata(&self) -> Bytes {
+        match *self {
+            Self::Transfer { recipient, amount } => {
+                IERC20::transferCall { to: recipient, amount }.abi_encode().into()
+            }
+            Self::Approve { spender, amount } => {
+                IERC20::approveCall { spender, amount }.abi_encode().into()
+            }
+        }
+    }
+
+    /// Human-readable description of this call on `token`, scaling amounts by `decimals`.
+    pub fn summary(&self, token: Address, decimals: u8) -> String {
+        let human =
+            |amount: U256| format_units(amount, decimals).unwrap_or_else(|_| amount.to_string());
+        match *self {
+            Self::Transfer { recipient, amount } => format!(
+                "ERC-20 transfer(to: {recipient}, amount: {} ({amount} base units)) on token {token}",
+                human(amount)
+            ),
+            Self::Approve { spender, amount } => format!(
+                "ERC-20 approve(spender: {spender}, amount: {} ({amount} base units)) on token {token}",
+                human(amount)
+            ),
+        }
+    }
+}
+
+/// Convert a human-readable token amount (e.g. `"12.5"`) to base units.
+///
+/// # Errors
+///
+/// Returns [`Error::Amount`] if the amount is negative, malformed, or has
+/// more fractional digits than `decimals`.
+pub fn parse_amount(amount: &str, decimals: u8) -> Result<U256, Error> {
+    let invalid = |e: eyre::Report| Error::Amount(amount.to_owned(), e);
+    // `parse_units` silently truncates excess fractional digits; refuse instead.
+    let fraction_digits = amount.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+    if fraction_digits > usize::from(decimals) {
+        return Err(invalid(eyre::eyre!("more than {decimals} fractional digits")));
+    }
+    match parse_units(amount, decimals).map_err(|e| invalid(eyre::eyre!(e)))? {
+        ParseUnits::U256(base_units) => Ok(base_units),
+        ParseUnits::I256(_) => Err(invalid(eyre::eyre!("amount must not be negative"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    #[test]
+    fn parses_amount_with_decimals() -> eyre::Result<()> {
+        assert_eq!(parse_amount("12.5", 6)?, U256::from(12_500_000));
+        assert_eq!(parse_amount("1", 18)?, U256::from(10).pow(U256::from(18)));
+        assert!(parse_amount("0.0000001", 6).is_err());
+        assert!(parse_amount("-1", 6).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn encodes_transfer_and_approve() {
+        let account = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+        let amount = U256::from(1000);
+        let transfer = Erc20Call::Transfer { recipient: account, amount }.calldata();
+        let approve = Erc20Call::Approve { spender: account, amount }.calldata();
+        assert_eq!(transfer[..4], hex::decode("a9059cbb").unwrap());
+        assert_eq!(approve[..4], hex::decode("095ea7b3").unwrap());
+        assert_eq!(transfer[4..], approve[4..]);
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+
+use super::*;
+use alloy::consensus::{
+    SignableTransaction, Transaction, TxType, transaction::RlpEcdsaEncodableTx,
+};
+use alloy::eips::{eip2930::AccessList, eip7702::SignedAuthorization};
+use alloy::primitives::{
+    Address, B256, Bytes, TxHash, TxKind,
+    utils::{format_ether, format_units},
+};
+use alloy::signers::Signature;
+
+/// Errors for offline transaction inspection.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to recover the sender from the transaction signature: {0}
+    Recover(eyre::Report),
+    /// Unsupported transaction type {0:#04x}
+    UnsupportedType(u8),
+}
+
+/// Every field of a signed transaction, plus its recovered sender and hash.
+#[derive(Debug, Clone)]
+pub struct Inspection {
+    /// EIP-2718 transaction type.
+    pub tx_type: TxType,
+    /// Transaction hash, as reported by nodes and block explorers.
+    pub hash: TxHash,
+    /// Sender address recovered from the signature.
+    pub sender: Address,
+    /// EIP-155 chain ID; `None` for pre-EIP-155 legacy transactions.
+    pub chain_id: Option<u64>,
+    /// Sender nonce.
+    pub nonce: u64,
+    /// Maximum gas units the transaction may consume.
+    pub gas_limit: u64,
+    /// Gas price in Wei, for legacy and EIP-2930 transactions.
+    pub gas_price: Option<u128>,
+    /// Maximum total fee per gas in Wei, for dynamic-fee transactions.
+    pub max_fee_per_gas: Option<u128>,
+    /// Maximum priority fee per gas in Wei, for dynamic-fee transactions.
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Maximum fee per blob gas in Wei, for EIP-4844 transactions.
+    pub max_fee_per_blob_gas: Option<u128>,
+    /// Recipient, or contract creation.
+    pub to: TxKind,
+    /// Amount of Wei transferred.
+    pub value: Wei,
+    /// Calldata or init-code.
+    pub input: Bytes,
+    /// Access list, for EIP-2930 and later transaction types.
+    pub access_list: Option<AccessList>,
+    /// Versioned hashes of the attached blobs, for EIP-4844 transactions.
+    pub blob_versioned_hashes: Option<Vec<B256>>,
+    /// Signed delegations, for EIP-7702 transactions.
+    pub authorization_list: Option<Vec<SignedAuthorization>>,
+}
+
+impl<T> TxSigned<T>
+where
+    T: Transaction + SignableTransaction<Signature> + RlpEcdsaEncodableTx,
+{
+    /// Collect every field of this transaction, recovering its sender offline.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Recover`] if the signature does not recover to a valid address.
+    pub fn inspect(&self) -> Result<Inspection, Error> {
+        let tx = self.0.tx();
+        let sender = self
+            .0
+            .signature()
+            .recover_address_from_prehash(&self.0.signature_hash())
+            .map_err(|e| Error::Recover(eyre::eyre!(e)))?;
+        let tx_type = TxType::try_from(tx.ty()).map_err(|_| Error::UnsupportedType(tx.ty()))?;
+        let dynamic_fee = tx.is_dynamic_fee();
+
+        Ok(Inspection {
+            tx_type,
+            hash: *self.0.hash(),
+            sender,
+            chain_id: tx.chain_id(),
+            nonce: tx.nonce(),
+            gas_limit: tx.gas_limit(),
+            gas_price: tx.gas_price(),
+            max_fee_per_gas: dynamic_fee.then(|| tx.max_fee_per_gas()),
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas(),
+            max_fee_per_blob_gas: tx.max_fee_per_blob_gas(),
+            to: tx.kind(),
+            value: tx.value(),
+            input: tx.input().clone(),
+            access_list: tx.access_list().cloned(),
+            blob_versioned_hashes: tx.blob_versioned_hashes().map(<[B256]>::to_vec),
+            authorization_list: tx.authorization_list().map(<[SignedAuthorization]>::to_vec),
+        })
+    }
+}
+
+impl AnyTxSigned {
+    /// Collect every field of this transaction, recovering its sender offline.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Recover`] if the signature does not recover to a valid address.
+    pub fn inspect(&self) -> Result<Inspection, Error> {
+        match self {
+            Self::Legacy(signed) => signed.inspect(),
+            Self::Eip2930(signed) => signed.inspect(),
+            Self::Eip1559(signed) => signed.inspect(),
+            Self::Eip4844(signed) => signed.inspect(),
+            Self::Eip7702(signed) => signed.inspect(),
+        }
+    }
+}
+
+/// Drop insignificant trailing zeros from a formatted decimal.
+pub(crate) fn trim_decimal(decimal: &str) -> &str {
+    if decimal.contains('.') {
+        decimal.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        decimal
+    }
+}
+
+/// Format a per-gas fee in Wei with its Gwei equivalent.
+fn fee(wei: u128) -> String {
+    let gwei = format_units(wei, "gwei").unwrap_or_default();
+    format!("{wei} wei ({} gwei)", trim_decimal(&gwei))
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Type:                     {} ({:#04x})", self.tx_type, self.tx_type as u8)?;
+        writeln!(f, "Hash:                     {}", self.hash)?;
+        writeln!(f, "Sender:                   {}", self.sender)?;
+        match self.chain_id {
+            Some(chain_id) => writeln!(f, "Chain ID:                 {chain_id}")?,
+            None => writeln!(f, "Chain ID:                 (none, replayable on any chain)")?,
+        }
+        writeln!(f, "Nonce:                    {}", self.nonce)?;
+        writeln!(f, "Gas limit:                {}", self.gas_limit)?;
+        if let Some(gas_price) = self.gas_price {
+            writeln!(f, "Gas price:                {}", fee(gas_price))?;
+        }
+        if let Some(max_fee_per_gas) = self.max_fee_per_gas {
+            writeln!(f, "Max fee per gas:          {}", fee(max_fee_per_gas))?;
+        }
+        if let Some(max_priority_fee_per_gas) = self.max_priority_fee_per_gas {
+            writeln!(f, "Max priority fee per gas: {}", fee(max_priority_fee_per_gas))?;
+        }
+        if let Some(max_fee_per_blob_gas) = self.max_fee_per_blob_gas {
+            writeln!(f, "Max fee per blob gas:     {}", fee(max_fee_per_blob_gas))?;
+        }
+        match self.to {
+            TxKind::Call(to) => writeln!(f, "To:                       {to}")?,
+            TxKind::Create => writeln!(f, "To:                       (contract creation)")?,
+        }
+        writeln!(f, "Value:                    {} ETH", trim_decimal(&format_ether(self.value)))?;
+        writeln!(f, "Input ({} bytes):", self.input.len())?;
+        writeln!(f, "  {}", self.input)?;
+        if let Some(access_list) = self.access_list.as_ref().filter(|list| !list.is_empty()) {
+            writeln!(f, "Access list:")?;
+            for item in access_list.iter() {
+                writeln!(f, "  {}", item.address)?;
+                for key in &item.storage_keys {
+                    writeln!(f, "    {key}")?;
+                }
+            }
+        }
+        if let Some(hashes) = &self.blob_versioned_hashes {
+            writeln!(f, "Blob versioned hashes:")?;
+            for hash in hashes {
+                writeln!(f, "  {hash}")?;
+            }
+        }
+        if let Some(authorizations) = &self.authorization_list {
+            writeln!(f, "Authorizations:")?;
+            for auth in authorizations {
+                let authority = auth
+                    .signature()
+                    .and_then(|sig| sig.recover_address_from_prehash(&auth.signature_hash()))
+                    .map_or_else(|_| "(invalid signature)".to_owned(), |a| a.to_string());
+                writeln!(
+                    f,
+                    "  authority {authority} delegates to {} (chain ID {}, nonce {})",
+                    auth.address, auth.chain_id, auth.nonce
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommonPayload, Eip1559Payload, sign::Build};
+    use alloy::consensus::TxEip1559;
+    use alloy::primitives::{U256, keccak256};
+    use alloy::signers::local::PrivateKeySigner;
+
+    #[test]
+    fn inspects_sender_hash_and_fields() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let to = Address::repeat_byte(0x11);
+        let common_payload = CommonPayload {
+            chain_id: 11155111,
+            nonce: 7,
+            gas_limit: 21_000,
+            to: Some(to),
+            value: U256::from(1_000_000_000_000_000_u64),
+            input: Bytes::new(),
+        };
+        let payload = Eip1559Payload {
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_500_000_000,
+            access_list: AccessList::default(),
+        };
+        let signed_bytes: TxEip2718Bytes<TxEip1559> =
+            common_payload.build(payload)?.sign(&signer)?.encode_2718();
+        let hash = keccak256(&signed_bytes);
+
+        let inspection = signed_bytes.decode_2718()?.inspect()?;
+        assert_eq!(inspection.tx_type, TxType::Eip1559);
+        assert_eq!(inspection.hash, hash);
+        assert_eq!(inspection.sender, signer.address());
+        assert_eq!(inspection.chain_id, Some(11155111));
+        assert_eq!(inspection.nonce, 7);
+        assert_eq!(inspection.gas_price, None);
+        assert_eq!(inspection.to, TxKind::Call(to));
+
+        let text = inspection.to_string();
+        assert!(text.contains("Type:                     EIP-1559 (0x02)"));
+        assert!(text.contains("Max priority fee per gas: 1500000000 wei (1.5 gwei)"));
+        assert!(text.contains("Value:                    0.001 ETH"));
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use super::*;
+use alloy::signers::local::PrivateKeySigner;
+
+/// Errors for Web3 Secret Storage (V3 keystore) files.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to decrypt the keystore (wrong password or malformed file): {0}
+    Decrypt(eyre::Report),
+    /// Failed to encrypt and write the keystore: {0}
+    Encrypt(eyre::Report),
+}
+
+/// Decrypt a V3 keystore file, accepting both scrypt and pbkdf2 key derivation.
+///
+/// # Errors
+///
+/// Returns [`Error::Decrypt`] if the file cannot be read, is not a valid V3 keystore,
+/// or the password is wrong.
+pub fn decrypt<P, S>(path: P, password: S) -> Result<PrivateKeySigner, Error>
+where
+    P: AsRef<Path>,
+    S: AsRef<[u8]>,
+{
+    PrivateKeySigner::decrypt_keystore(path, password).map_err(|e| Error::Decrypt(eyre::eyre!(e)))
+}
+
+/// Generate a random key and store it in `dir` as a scrypt-encrypted V3 keystore.
+///
+/// The file is named `name`, or a random UUID if `None`. Returns the new signer and
+/// the path of the written file.
+///
+/// # Errors
+///
+/// Returns [`Error::Encrypt`] if a file named `name` already exists, or if the directory
+/// or file cannot be written.
+pub fn create<P, S>(
+    dir: P,
+    password: S,
+    name: Option<&str>,
+) -> Result<(PrivateKeySigner, PathBuf), Error>
+where
+    P: AsRef<Path>,
+    S: AsRef<[u8]>,
+{
+    let signer = PrivateKeySigner::random();
+    let path = import(dir, &signer, password, name)?;
+    Ok((signer, path))
+}
+
+/// Store an existing key in `dir` as a scrypt-encrypted V3 keystore.
+///
+/// The file is named `name`, or a random UUID if `None`. Returns the path of the written file.
+///
+/// # Errors
+///
+/// Returns [`Error::Encrypt`] if a file named `name` already exists, or if the directory
+/// or file cannot be written.
+pub fn import<P, S>(
+    dir: P,
+    signer: &PrivateKeySigner,
+    password: S,
+    name: Option<&str>,
+) -> Result<PathBuf, Error>
+where
+    P: AsRef<Path>,
+    S: AsRef<[u8]>,
+{
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir).map_err(|e| Error::Encrypt(eyre::eyre!(e)))?;
+    // Claim the file name atomically so a concurrent import cannot take it between the
+    // check and the write; the encryption below then overwrites the empty file.
+    let claimed = match name.map(|name| dir.join(name)) {
+        Some(path) => match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => Some(path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                return Err(Error::Encrypt(eyre::eyre!("{} already exists", path.display())));
+            }
+            Err(e) => return Err(Error::Encrypt(eyre::eyre!(e))),
+        },
+        None => None,
+    };
+    let (_, uuid) = PrivateKeySigner::encrypt_keystore(
+        dir,
+        &mut rand::thread_rng(),
+        signer.to_bytes(),
+        password,
+        name,
+    )
+    .map_err(|e| {
+        if let Some(path) = &claimed {
+            let _ = std::fs::remove_file(path);
+        }
+        Error::Encrypt(eyre::eyre!(e))
+    })?;
+    Ok(dir.join(name.unwrap_or(&uuid)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::b256;
+
+    #[test]
+    fn decrypts_pbkdf2_test_vector() -> eyre::Result<()> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/keystore-pbkdf2.json");
+        let signer = decrypt(path, "testpassword")?;
+        assert_eq!(
+            signer.to_bytes(),
+            b256!("0x7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d")
+        );
+        assert!(matches!(decrypt(path, "wrongpassword"), Err(Error::Decrypt(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn import_round_trips_through_scrypt() -> eyre::Result<()> {
+        let dir = std::env::temp_dir().join(format!("eth-offline-signer-{}", std::process::id()));
+        let signer = PrivateKeySigner::random();
+        let path = import(&dir, &signer, "hunter2", Some("imported.json"))?;
+        assert_eq!(path, dir.join("imported.json"));
+        let existing = import(&dir, &signer, "hunter2", Some("imported.json"));
+        assert!(matches!(existing, Err(Error::Encrypt(_))));
+        let decrypted = decrypt(&path, "hunter2")?;
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(decrypted.address(), signer.address());
+        Ok(())
+    }
+}
@@ -1,22 +1,73 @@
 use std::marker::PhantomData;
 
-use alloy::consensus::Signed;
+use alloy::consensus::{
+    Signed, TxEip1559, TxEip2930, TxEip4844WithSidecar, TxEip7702, TxLegacy, TxType,
+};
+use alloy::primitives::Bytes;
 use color_eyre::eyre;
 use displaydoc::Display;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub mod abi;
+pub mod batch;
 pub mod confirm;
+pub mod envelope;
+pub mod erc20;
+pub mod inspect;
+pub mod keystore;
+pub mod logs;
+pub mod mnemonic;
+pub mod output;
+pub mod prepare;
+pub mod qr;
+pub mod remote;
+pub mod replace;
+pub mod revert;
 pub mod sign;
 pub mod submit;
 
 pub use alloy::primitives::U256 as Wei;
 pub use confirm::get_receipt;
-pub use sign::{CommonPayload, Eip1559Payload, LegacyPayload};
+pub use envelope::Envelope;
+pub use sign::{
+    CommonPayload, Eip1559Payload, Eip2930Payload, Eip4844Payload, Eip7702Payload, LegacyPayload,
+    UniquePayload, UnsignedTx,
+};
 
 /// Wrapper type indicating a transaction has been signed.
 pub struct TxSigned<T>(Signed<T>);
 
+/// A signed transaction of any supported type, detected from its EIP-2718 type byte.
+pub enum AnyTxSigned {
+    /// Legacy (Type-0) transaction.
+    Legacy(TxSigned<TxLegacy>),
+    /// EIP-2930 (Type-1) transaction.
+    Eip2930(TxSigned<TxEip2930>),
+    /// EIP-1559 (Type-2) transaction.
+    Eip1559(TxSigned<TxEip1559>),
+    /// EIP-4844 (Type-3) transaction in its network form, including the blob sidecar.
+    Eip4844(TxSigned<TxEip4844WithSidecar>),
+    /// EIP-7702 (Type-4) transaction.
+    Eip7702(TxSigned<TxEip7702>),
+}
+
+impl AnyTxSigned {
+    /// The EIP-2718 type of the wrapped transaction.
+    pub fn tx_type(&self) -> TxType {
+        match self {
+            Self::Legacy(_) => TxType::Legacy,
+            Self::Eip2930(_) => TxType::Eip2930,
+            Self::Eip1559(_) => TxType::Eip1559,
+            Self::Eip4844(_) => TxType::Eip4844,
+            Self::Eip7702(_) => TxType::Eip7702,
+        }
+    }
+}
+
 /// Container for an EIP-2718 envelope–encoded signed transaction.
+///
+/// For EIP-4844 transactions this holds the network (pooled) form, including the blob sidecar.
 pub struct TxEip2718Bytes<T>(Vec<u8>, std::marker::PhantomData<T>);
 
 impl<T> AsRef<[u8]> for TxEip2718Bytes<T> {
@@ -32,6 +83,31 @@ impl<T> TxEip2718Bytes<T> {
     }
 }
 
+impl<T> Clone for TxEip2718Bytes<T> {
+    fn clone(&self) -> Self {
+        Self::from_untyped(self.0.clone())
+    }
+}
+
+impl<T> std::fmt::Debug for TxEip2718Bytes<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TxEip2718Bytes({})", alloy::hex::encode_prefixed(&self.0))
+    }
+}
+
+/// Serialized as a `0x`-prefixed hex string, like raw transactions in JSON-RPC.
+impl<T> Serialize for TxEip2718Bytes<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&alloy::hex::encode_prefixed(&self.0))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for TxEip2718Bytes<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Bytes::deserialize(deserializer).map(|bytes| Self::from_untyped(bytes.to_vec()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::sign::{Build, CommonPayload};
@@ -39,10 +115,18 @@ mod tests {
     use super::*;
 
     use alloy::{
-        consensus::{TxEip1559, TxEnvelope, TxLegacy},
-        eips::Decodable2718,
+        consensus::{
+            SidecarCoder, SimpleCoder, TxEip1559, TxEip2930, TxEip4844WithSidecar, TxEip7702,
+            TxEnvelope, TxLegacy,
+        },
+        eips::{
+            Decodable2718,
+            eip2930::{AccessList, AccessListItem},
+            eip4844::{MAX_BLOBS_PER_BLOCK_DENCUN, env_settings::EnvKzgSettings},
+            eip7702::Authorization,
+        },
         node_bindings::Anvil,
-        primitives::U256,
+        primitives::{Address, B256, Bytes, TxKind, U256, bytes},
         providers::{Provider, ProviderBuilder},
         signers::local::PrivateKeySigner,
     };
@@ -56,8 +140,9 @@ mod tests {
             let payload = Eip1559Payload {
                 max_fee_per_gas: 20_000_000_000,
                 max_priority_fee_per_gas: 1_000_000_000,
+                access_list: AccessList::default(),
             };
-            let signed_bytes = common_payload.clone().build(payload).sign(signer)?.encode_2718();
+            let signed_bytes = common_payload.clone().build(payload)?.sign(signer)?.encode_2718();
             Ok(signed_bytes)
         }
         smoke_test::<TxEip1559>(callback_sign).await?;
@@ -65,6 +150,256 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn smoke_test_e2930() -> eyre::Result<()> {
+        fn callback_sign(
+            common_payload: CommonPayload,
+            signer: &PrivateKeySigner,
+        ) -> eyre::Result<TxEip2718Bytes<TxEip2930>> {
+            // Each access list entry is charged on top of the intrinsic 21,000 gas.
+            let common_payload = CommonPayload { gas_limit: 30_000, ..common_payload };
+            let payload = Eip2930Payload {
+                gas_price: 20_000_000_000,
+                access_list: AccessList(vec![AccessListItem {
+                    address: common_payload.to.unwrap_or_default(),
+                    storage_keys: vec![B256::ZERO],
+                }]),
+            };
+            let signed_bytes = common_payload.clone().build(payload)?.sign(signer)?.encode_2718();
+            Ok(signed_bytes)
+        }
+        smoke_test::<TxEip2930>(callback_sign).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn smoke_test_e4844() -> eyre::Result<()> {
+        fn callback_sign(
+            common_payload: CommonPayload,
+            signer: &PrivateKeySigner,
+        ) -> eyre::Result<TxEip2718Bytes<TxEip4844WithSidecar>> {
+            let payload = Eip4844Payload {
+                max_fee_per_gas: 20_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                max_fee_per_blob_gas: 1_000_000_000,
+                access_list: AccessList::default(),
+                sidecar: sign::blob_sidecar([b"smoke test blob"])?,
+            };
+            let signed_bytes = common_payload.clone().build(payload)?.sign(signer)?.encode_2718();
+            Ok(signed_bytes)
+        }
+        smoke_test::<TxEip4844WithSidecar>(callback_sign).await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn e4844_round_trip_keeps_sidecar() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let common_payload = CommonPayload {
+            chain_id: 1,
+            nonce: 0,
+            gas_limit: 21_000,
+            to: Some(Address::ZERO),
+            value: U256::ZERO,
+            input: Bytes::new(),
+        };
+        let sidecar = sign::blob_sidecar([b"first".as_slice(), b"second".as_slice()])?;
+        let payload = Eip4844Payload {
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            max_fee_per_blob_gas: 1_000_000_000,
+            access_list: AccessList::default(),
+            sidecar: sidecar.clone(),
+        };
+        let signed_bytes: TxEip2718Bytes<TxEip4844WithSidecar> =
+            common_payload.build(payload)?.sign(&signer)?.encode_2718();
+        assert_eq!(signed_bytes.as_ref()[0], 0x03);
+
+        let TxSigned(signed) = signed_bytes.decode_2718()?;
+        assert_eq!(signed.tx().sidecar, sidecar);
+        assert_eq!(signed.tx().tx.blob_versioned_hashes.len(), 2);
+        signed.tx().validate_blob(EnvKzgSettings::Default.get())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn blob_sidecar_encodes_data_and_limits_blob_count() -> eyre::Result<()> {
+        let data = vec![0xffu8; 200_000];
+        let sidecar = sign::blob_sidecar([&data])?;
+        assert_eq!(sidecar.blobs.len(), 2);
+        assert_eq!(SimpleCoder::default().decode_all(&sidecar.blobs), Some(vec![data]));
+
+        let blobs = vec![b"blob"; MAX_BLOBS_PER_BLOCK_DENCUN + 1];
+        assert!(matches!(
+            sign::blob_sidecar(blobs),
+            Err(sign::Error::TooManyBlobs(7, MAX_BLOBS_PER_BLOCK_DENCUN))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn smoke_test_e7702() -> eyre::Result<()> {
+        fn callback_sign(
+            common_payload: CommonPayload,
+            signer: &PrivateKeySigner,
+        ) -> eyre::Result<TxEip2718Bytes<TxEip7702>> {
+            // Self-sponsored delegation: the authority nonce is bumped by the transaction itself.
+            let authorization = Authorization {
+                chain_id: U256::from(common_payload.chain_id),
+                address: Address::repeat_byte(0x77),
+                nonce: common_payload.nonce + 1,
+            };
+            // Each authorization tuple is charged on top of the intrinsic 21,000 gas.
+            let common_payload = CommonPayload { gas_limit: 50_000, ..common_payload };
+            let payload = Eip7702Payload {
+                max_fee_per_gas: 20_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                access_list: AccessList::default(),
+                authorization_list: vec![sign::sign_authorization(authorization, signer)?],
+            };
+            let signed_bytes = common_payload.clone().build(payload)?.sign(signer)?.encode_2718();
+            Ok(signed_bytes)
+        }
+        smoke_test::<TxEip7702>(callback_sign).await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn authorization_recovers_to_signer() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let authorization =
+            Authorization { chain_id: U256::ZERO, address: Address::repeat_byte(0x77), nonce: 7 };
+        let signed = sign::sign_authorization(authorization.clone(), &signer)?;
+        assert_eq!(signed.inner(), &authorization);
+
+        let authority =
+            signed.signature()?.recover_address_from_prehash(&authorization.signature_hash())?;
+        assert_eq!(authority, signer.address());
+
+        Ok(())
+    }
+
+    #[test]
+    fn calldata_is_carried_in_envelope() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let input = bytes!("a9059cbb");
+        let common_payload = CommonPayload {
+            chain_id: 1,
+            nonce: 0,
+            gas_limit: 50_000,
+            to: Some(Address::ZERO),
+            value: U256::ZERO,
+            input: input.clone(),
+        };
+
+        let payload = Eip1559Payload {
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            access_list: AccessList::default(),
+        };
+        let signed_bytes: TxEip2718Bytes<TxEip1559> =
+            common_payload.clone().build(payload)?.sign(&signer)?.encode_2718();
+        assert_eq!(signed_bytes.decode_2718()?.0.tx().input, input);
+
+        let payload = LegacyPayload { gas_price: 20_000_000_000 };
+        let signed_bytes: TxEip2718Bytes<TxLegacy> =
+            common_payload.build(payload)?.sign(&signer)?.encode_2718();
+        assert_eq!(signed_bytes.decode_2718()?.0.tx().input, input);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sign_async_matches_sync_signing() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let common_payload = CommonPayload {
+            chain_id: 1,
+            nonce: 0,
+            gas_limit: 21_000,
+            to: Some(Address::ZERO),
+            value: U256::from(1),
+            input: Bytes::new(),
+        };
+        let payload = || LegacyPayload { gas_price: 20_000_000_000 };
+
+        let sync_bytes: TxEip2718Bytes<TxLegacy> =
+            common_payload.clone().build(payload())?.sign(&signer)?.encode_2718();
+        let async_bytes: TxEip2718Bytes<TxLegacy> =
+            common_payload.build(payload())?.sign_async(&signer).await?.encode_2718();
+        // RFC 6979 signatures are deterministic, so both paths yield the same envelope.
+        assert_eq!(sync_bytes.as_ref(), async_bytes.as_ref());
+
+        Ok(())
+    }
+
+    #[test]
+    fn deploy_creates_contract_at_predicted_address() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let common_payload = CommonPayload {
+            chain_id: 1,
+            nonce: 5,
+            gas_limit: 100_000,
+            to: None,
+            value: U256::ZERO,
+            input: bytes!("6080604052"),
+        };
+        assert_eq!(
+            common_payload.contract_address(signer.address()),
+            Some(signer.address().create(5))
+        );
+
+        let payload = LegacyPayload { gas_price: 20_000_000_000 };
+        let signed_bytes: TxEip2718Bytes<TxLegacy> =
+            common_payload.clone().build(payload)?.sign(&signer)?.encode_2718();
+        assert_eq!(signed_bytes.decode_2718()?.0.tx().to, TxKind::Create);
+
+        let payload = Eip7702Payload {
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            access_list: AccessList::default(),
+            authorization_list: vec![],
+        };
+        let result: Result<sign::Tx<TxEip7702>, _> = common_payload.build(payload);
+        assert!(matches!(result, Err(sign::Error::MissingRecipient)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn any_tx_signed_detects_type() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let common_payload = CommonPayload {
+            chain_id: 1,
+            nonce: 0,
+            gas_limit: 21_000,
+            to: Some(Address::ZERO),
+            value: U256::ZERO,
+            input: Bytes::new(),
+        };
+
+        let payload = LegacyPayload { gas_price: 20_000_000_000 };
+        let signed_bytes: TxEip2718Bytes<TxLegacy> =
+            common_payload.clone().build(payload)?.sign(&signer)?.encode_2718();
+        let signed = AnyTxSigned::decode_2718(signed_bytes.as_ref())?;
+        assert_eq!(signed.tx_type(), TxType::Legacy);
+
+        let payload =
+            Eip2930Payload { gas_price: 20_000_000_000, access_list: AccessList::default() };
+        let signed_bytes: TxEip2718Bytes<TxEip2930> =
+            common_payload.build(payload)?.sign(&signer)?.encode_2718();
+        let signed = AnyTxSigned::decode_2718(signed_bytes.as_ref())?;
+        assert_eq!(signed.tx_type(), TxType::Eip2930);
+
+        assert!(matches!(AnyTxSigned::decode_2718(&[0x05, 0xc0]), Err(submit::Error::Decode(_))));
+        assert!(matches!(AnyTxSigned::decode_2718(&[]), Err(submit::Error::Decode(_))));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn smoke_test_legacy() -> eyre::Result<()> {
         fn callback_sign(
@@ -72,7 +407,7 @@ mod tests {
             signer: &PrivateKeySigner,
         ) -> eyre::Result<TxEip2718Bytes<TxLegacy>> {
             let payload = LegacyPayload { gas_price: 20_000_000_000 };
-            let signed_bytes = common_payload.clone().build(payload).sign(signer)?.encode_2718();
+            let signed_bytes = common_payload.clone().build(payload)?.sign(signer)?.encode_2718();
             Ok(signed_bytes)
         }
         smoke_test::<TxLegacy>(callback_sign).await?;
@@ -113,8 +448,9 @@ mod tests {
             chain_id: anvil.chain_id(),
             nonce,
             gas_limit: 21_000,
-            to: bob,
+            to: Some(bob),
             value: U256::from(1_000_000_000_000_000_u64),
+            input: Bytes::new(),
         };
         let signed_bytes = callback_sign(common_payload, &signer)?;
         println!("Signed transaction: {}", hex::encode(&signed_bytes));
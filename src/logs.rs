@@ -0,0 +1,181 @@
+use std::fmt;
+
+use super::*;
+use alloy::dyn_abi::EventExt;
+use alloy::json_abi::{Event, JsonAbi};
+use alloy::primitives::Address;
+use alloy::rpc::types::Log;
+
+/// Events of the ERC-20, ERC-721 and ERC-1155 token standards, decoded without an ABI.
+///
+/// ERC-20 and ERC-721 share the `Transfer` and `Approval` signatures and differ only in
+/// whether the last argument is indexed, so the number of topics tells them apart.
+pub const STANDARD_EVENTS: &[&str] = &[
+    "event Transfer(address indexed from, address indexed to, uint256 value)",
+    "event Approval(address indexed owner, address indexed spender, uint256 value)",
+    "event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)",
+    "event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId)",
+    "event ApprovalForAll(address indexed owner, address indexed operator, bool approved)",
+    "event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value)",
+    "event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values)",
+    "event URI(string value, uint256 indexed id)",
+];
+
+/// One decoded event argument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedParam {
+    /// Parameter name from the event declaration; empty if unnamed.
+    pub name: String,
+    /// Value in Solidity literal syntax.
+    pub value: String,
+}
+
+/// A receipt log matched against a known event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedLog {
+    /// Contract that emitted the log.
+    pub address: Address,
+    /// Position of the log in its block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_index: Option<u64>,
+    /// Event signature, e.g. `Transfer(address,address,uint256)`.
+    pub signature: String,
+    /// Arguments in declaration order, indexed or not.
+    pub params: Vec<DecodedParam>,
+}
+
+impl fmt::Display for DecodedLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.signature.split('(').next().unwrap_or_default();
+        let params = self
+            .params
+            .iter()
+            .map(|param| match param.name.as_str() {
+                "" => param.value.clone(),
+                name => format!("{name}: {}", param.value),
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{name}({})", params.join(", "))
+    }
+}
+
+/// Decodes receipt logs with the events of user ABIs and the [`STANDARD_EVENTS`].
+#[derive(Debug, Clone)]
+pub struct LogDecoder {
+    events: Vec<Event>,
+}
+
+impl LogDecoder {
+    /// Decoder trying the events of `abis` first, then the token standard events.
+    pub fn new(abis: &[JsonAbi]) -> Self {
+        let user = abis.iter().flat_map(|abi| abi.events()).filter(|event| !event.anonymous);
+        let standard = STANDARD_EVENTS.iter().filter_map(|event| Event::parse(event).ok());
+        Self { events: user.cloned().chain(standard).collect() }
+    }
+
+    /// Decode `log` with the first event whose selector and topic count match it.
+    ///
+    /// Returns `None` for logs of unknown events.
+    pub fn decode(&self, log: &Log) -> Option<DecodedLog> {
+        let data = log.data();
+        let topic0 = *data.topics().first()?;
+        self.events.iter().find_map(|event| {
+            let indexed = event.inputs.iter().filter(|input| input.indexed).count();
+            if event.selector() != topic0 || data.topics().len() != indexed + 1 {
+                return None;
+            }
+            let decoded = event.decode_log(data).ok()?;
+            let (mut indexed, mut body) = (decoded.indexed.iter(), decoded.body.iter());
+            let params = event
+                .inputs
+                .iter()
+                .map(|input| {
+                    let value = if input.indexed { indexed.next() } else { body.next() };
+                    Some(DecodedParam {
+                        name: input.name.clone(),
+                        value: abi::format_value(value?),
+                    })
+                })
+                .collect::<Option<_>>()?;
+            Some(DecodedLog {
+                address: log.address(),
+                log_index: log.log_index,
+                signature: event.signature(),
+                params,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, LogData, U256};
+    use alloy::sol_types::SolValue;
+
+    fn log(topics: Vec<B256>, data: Vec<u8>) -> Log {
+        let inner = LogData::new_unchecked(topics, data.into());
+        Log {
+            inner: alloy::primitives::Log { address: Address::repeat_byte(0xcc), data: inner },
+            log_index: Some(7),
+            ..Default::default()
+        }
+    }
+
+    fn selector(signature: &str) -> B256 {
+        alloy::primitives::keccak256(signature)
+    }
+
+    #[test]
+    fn standard_events_parse() {
+        for event in STANDARD_EVENTS {
+            assert!(Event::parse(event).is_ok(), "{event}");
+        }
+    }
+
+    #[test]
+    fn tells_erc20_and_erc721_transfers_apart() {
+        let decoder = LogDecoder::new(&[]);
+        let transfer = selector("Transfer(address,address,uint256)");
+        let from = Address::repeat_byte(0x01).into_word();
+        let to = Address::repeat_byte(0x02).into_word();
+
+        let erc20 = log(vec![transfer, from, to], U256::from(1_000).abi_encode());
+        let decoded = decoder.decode(&erc20).expect("ERC-20 transfer");
+        assert_eq!(decoded.log_index, Some(7));
+        assert_eq!(
+            decoded.to_string(),
+            format!(
+                "Transfer(from: {}, to: {}, value: 1000)",
+                Address::repeat_byte(0x01),
+                Address::repeat_byte(0x02)
+            )
+        );
+
+        let erc721 = log(vec![transfer, from, to, U256::from(42).into()], Vec::new());
+        let decoded = decoder.decode(&erc721).expect("ERC-721 transfer");
+        assert_eq!(decoded.params[2], DecodedParam { name: "tokenId".into(), value: "42".into() });
+
+        assert_eq!(decoder.decode(&log(vec![selector("Unknown()")], Vec::new())), None);
+    }
+
+    #[test]
+    fn decodes_events_of_user_abis() -> eyre::Result<()> {
+        let abi = abi::parse_json_abi(
+            r#"[{"type": "event", "name": "Deposit", "anonymous": false, "inputs": [
+                {"name": "account", "type": "address", "indexed": true},
+                {"name": "amounts", "type": "uint256[]", "indexed": false}]}]"#,
+        )?;
+        let decoder = LogDecoder::new(std::slice::from_ref(&abi));
+        let account = Address::repeat_byte(0x03);
+        let deposit = log(
+            vec![selector("Deposit(address,uint256[])"), account.into_word()],
+            vec![U256::from(1), U256::from(2)].abi_encode(),
+        );
+        let decoded = decoder.decode(&deposit).expect("user event");
+        assert_eq!(decoded.to_string(), format!("Deposit(account: {account}, amounts: [1,2])"));
+        Ok(())
+    }
+}
@@ -1,13 +1,27 @@
+use std::{
+    io::{IsTerminal, Read},
+    path::PathBuf,
+    sync::mpsc,
+    time::Duration,
+};
+
 use alloy::{
-    consensus::{TxEip1559, TxLegacy},
-    primitives::{Address, TxHash, utils},
-    signers::local::PrivateKeySigner,
+    consensus::SignableTransaction,
+    eips::{
+        eip2930::AccessList,
+        eip7702::{Authorization, SignedAuthorization},
+    },
+    primitives::{Address, Bytes, TxHash, U256, utils},
+    signers::{Signature, local::PrivateKeySigner},
 };
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use color_eyre::eyre;
 
-use eth_offline_signer::{self as lib, Eip1559Payload, LegacyPayload, TxEip2718Bytes, sign::Build};
+use eth_offline_signer::{
+    self as lib, Eip1559Payload, Eip2930Payload, Eip4844Payload, Eip7702Payload, LegacyPayload,
+    UniquePayload, sign::SignerBackend,
+};
 
 /// CLI for offline signing and RPC submission of Ethereum-compatible transactions
 #[derive(Parser)]
@@ -17,73 +31,728 @@ struct Cli {
     command: Command,
 }
 
+/// How `sign`, `submit` and `confirm` print their results.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Raw hex, a bare hash or a receipt dump, as read by people
+    Text,
+    /// One JSON object with stable camelCase fields, as read by scripts
+    Json,
+}
+
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant, reason = "parsed once per process")]
 enum Command {
     /// Offline-only transaction signing (no network calls)
     Sign {
-        /// 0x-prefixed private key for signing
-        #[arg(long, env = "PRIVATE_KEY")]
-        private_key: PrivateKeySigner,
+        #[command(flatten)]
+        key: KeyArgs,
+
+        /// Chain ID (e.g. 1 for Mainnet, 11155111 for Sepolia)
+        #[arg(long, required_unless_present_any = ["from_file", "original_hex", "original_file"])]
+        chain_id: Option<u64>,
+
+        /// Transaction nonce (pre-fetched from RPC)
+        #[arg(long, required_unless_present_any = ["from_file", "original_hex", "original_file"])]
+        nonce: Option<u64>,
+
+        /// Maximum gas units to allow for this transaction
+        #[clap(long, default_value = "21000")]
+        gas_limit: u64,
+
+        /// 0x-prefixed recipient address (omit when using `deploy`)
+        #[arg(long)]
+        to: Option<Address>,
+
+        /// Amount to send in ETH (e.g. "0.01"); required unless using `--from-file`, an ERC-20
+        /// mode, `replace` or `cancel`
+        #[arg(id = "eth", long, value_parser = utils::parse_ether)]
+        value: Option<lib::Wei>,
+
+        /// Hex-encoded calldata (e.g. "0xa9059cbb...")
+        #[arg(long, conflicts_with = "data_file")]
+        data: Option<Bytes>,
+
+        /// File containing hex-encoded calldata, or `-` to read it from stdin
+        #[arg(long)]
+        data_file: Option<PathBuf>,
+
+        /// Unsigned transaction envelope written by `prepare`, replacing all transaction options
+        #[arg(
+            long,
+            conflicts_with_all = ["chain_id", "nonce", "gas_limit", "to", "eth", "data", "data_file"]
+        )]
+        from_file: Option<PathBuf>,
+
+        #[command(flatten)]
+        original: OriginalArgs,
+
+        /// Print a signed transaction envelope (JSON) instead of the raw transaction hex
+        #[arg(long, conflicts_with = "output")]
+        envelope: bool,
+
+        /// Print the result as text or as one JSON object
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+
+        /// Human-readable description recorded in the envelope, with `--envelope` or `--output json`
+        /// (defaults to the one in `--from-file`)
+        #[arg(long)]
+        description: Option<String>,
+
+        #[command(flatten)]
+        qr: QrArgs,
+
+        /// Specify fee model and parameters, or a signing mode (omit with `--from-file`)
+        #[command(subcommand)]
+        mode: Option<SignMode>,
+    },
+
+    /// Offline signing of a CSV or JSON payment manifest with sequential nonces (no network calls)
+    SignBatch {
+        #[command(flatten)]
+        key: KeyArgs,
 
         /// Chain ID (e.g. 1 for Mainnet, 11155111 for Sepolia)
         #[arg(long)]
         chain_id: u64,
 
-        /// Transaction nonce (pre-fetched from RPC)
+        /// Nonce of the first payment (pre-fetched from RPC); each following row uses the next one
         #[arg(long)]
         nonce: u64,
 
-        /// Maximum gas units to allow for this transaction
+        /// Gas limit of rows without a `gasLimit` column
         #[clap(long, default_value = "21000")]
         gas_limit: u64,
 
-        /// 0x-prefixed recipient address
+        /// Manifest with `to`, `eth` and optional `data`, `gasLimit`, `description` per row: a CSV
+        /// file with a header row or a JSON array; `-` reads it from stdin
         #[arg(long)]
-        to: Address,
+        manifest: PathBuf,
+
+        /// Fee model and parameters shared by every payment
+        #[command(subcommand)]
+        unique_args: UniqueArgs,
+    },
+
+    /// Fetch the nonce, chain ID, fees and a gas estimate, and write an unsigned transaction file
+    Prepare {
+        /// 0x-prefixed address of the account that will sign the transaction
+        #[arg(long)]
+        from: Address,
+
+        /// 0x-prefixed recipient address (omit to deploy the init-code given as calldata)
+        #[arg(long)]
+        to: Option<Address>,
 
         /// Amount to send in ETH (e.g. "0.01")
-        #[arg(id = "eth", long, value_parser = utils::parse_ether)]
+        #[arg(id = "eth", long, value_parser = utils::parse_ether, default_value = "0")]
         value: lib::Wei,
 
-        /// Specify fee model and parameters
-        #[command(subcommand)]
-        unique_args: UniqueArgs,
+        /// Hex-encoded calldata (e.g. "0xa9059cbb...")
+        #[arg(long, conflicts_with = "data_file")]
+        data: Option<Bytes>,
+
+        /// File containing hex-encoded calldata, or `-` to read it from stdin
+        #[arg(long)]
+        data_file: Option<PathBuf>,
+
+        /// Prepare a legacy transaction priced by `eth_gasPrice` instead of an EIP-1559 one
+        #[arg(long)]
+        legacy: bool,
+
+        /// File to write the unsigned transaction envelope (JSON) to, for `sign --from-file`
+        #[arg(long)]
+        out_file: PathBuf,
+
+        /// Human-readable description recorded in the envelope (e.g. "Pay invoice #42")
+        #[arg(long)]
+        description: Option<String>,
+
+        #[command(flatten)]
+        qr: QrArgs,
+
+        /// JSON-RPC endpoint URL
+        #[arg(long, env = "RPC_URL")]
+        rpc_url: url::Url,
+    },
+
+    /// Offline-only signing of an EIP-7702 authorization tuple, printed as JSON (no network calls)
+    SignAuthorization {
+        #[command(flatten)]
+        key: KeyArgs,
+
+        /// Chain ID the authorization is valid on (0 for any chain)
+        #[arg(long)]
+        chain_id: u64,
+
+        /// 0x-prefixed address of the contract to delegate to
+        #[arg(long)]
+        address: Address,
+
+        /// Authority nonce (transaction nonce + 1 if the authority also sends the transaction)
+        #[arg(long)]
+        nonce: u64,
     },
 
     /// Submit a previously signed raw transaction via JSON-RPC
     Submit {
-        /// Transaction type: EIP-1559 (Type 2) or Legacy (Type 0)
+        /// Expected transaction type; detected from the envelope when omitted, checked otherwise
         #[arg(value_enum)]
-        tx_type: TxType,
+        tx_type: Option<TxType>,
 
         /// Signed and EIP-2718-encoded transaction hex (without `0x` prefix)
+        /// - Begins with `04` for EIP-7702 transactions
+        /// - Begins with `03` for EIP-4844 transactions (network form with sidecar)
         /// - Begins with `02` for EIP-1559 transactions
+        /// - Begins with `01` for EIP-2930 transactions
         /// - Begins with `f8` for Legacy transactions
-        #[arg(long)]
-        signed_hex: String,
+        #[arg(long, required_unless_present = "signed_file")]
+        signed_hex: Option<String>,
+
+        /// File containing the signed transaction hex or a signed envelope written by `sign --envelope`
+        #[arg(long, conflicts_with = "signed_hex")]
+        signed_file: Option<PathBuf>,
+
+        /// Print the result as text or as one JSON object
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+
+        /// JSON-RPC endpoint URL
+        #[arg(long, env = "RPC_URL")]
+        rpc_url: url::Url,
+    },
+
+    /// Submit signed transactions in nonce order via JSON-RPC and print a status table
+    SubmitBatch {
+        /// File with a JSON array of signed envelopes (as written by `sign-batch`), a single
+        /// signed envelope, or one signed transaction hex per line; repeat for multiple files
+        #[arg(long = "signed-file", required = true)]
+        signed_files: Vec<PathBuf>,
+
+        /// Whether to skip a sender's remaining transactions after its failure or submit them anyway
+        #[arg(long, value_enum, default_value = "stop")]
+        on_failure: FailurePolicy,
 
         /// JSON-RPC endpoint URL
         #[arg(long, env = "RPC_URL")]
         rpc_url: url::Url,
     },
 
-    /// Wait until a transaction is first included in a block and print its receipt
+    /// Decode a signed raw transaction and print every field, its sender and hash (no network calls)
+    Inspect {
+        /// Expected transaction type; detected from the envelope when omitted, checked otherwise
+        #[arg(value_enum)]
+        tx_type: Option<TxType>,
+
+        /// Signed and EIP-2718-encoded transaction hex (without `0x` prefix)
+        #[arg(long, required_unless_present = "signed_file")]
+        signed_hex: Option<String>,
+
+        /// File containing the signed transaction hex or a signed envelope written by `sign --envelope`
+        #[arg(long, conflicts_with = "signed_hex")]
+        signed_file: Option<PathBuf>,
+    },
+
+    /// Wait until a transaction is included and buried under enough blocks, and print its receipt;
+    /// exits with 3 if it reverted, 4 on timeout, 5 if dropped by a reorg and 6 on RPC failure
     Confirm {
         /// 0x-prefixed transaction hash to monitor
         #[arg(long)]
         tx_hash: TxHash,
 
+        /// Blocks that must include or follow the receipt's block (1 = first inclusion)
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        confirmations: u64,
+
+        /// Give up after this long (e.g. "90s", "5m"); 0 waits indefinitely
+        #[arg(long, default_value = "5m", value_parser = parse_duration)]
+        timeout: Duration,
+
+        /// Delay between RPC polls (e.g. "500ms", "2s")
+        #[arg(long, default_value = "2s", value_parser = parse_duration)]
+        poll_interval: Duration,
+
+        /// If the transaction reverted, replay it with `eth_call` on its parent block and decode why
+        #[arg(long)]
+        revert_reason: bool,
+
+        /// JSON ABI or compiler artifact declaring custom errors and events; repeat for several contracts
+        #[arg(long = "abi")]
+        abis: Vec<PathBuf>,
+
+        /// Print the result as text or as one JSON object
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+
         /// JSON-RPC endpoint URL for polling (or use RPC_URL env var)
         #[arg(long, env = "RPC_URL")]
         rpc_url: url::Url,
     },
 
+    /// Create, import and export encrypted V3 keystore files (no network calls)
+    Keystore {
+        #[command(subcommand)]
+        command: KeystoreCommand,
+    },
+
+    /// List the first accounts derived from a BIP-39 mnemonic (no network calls)
+    Addresses {
+        /// File containing a BIP-39 mnemonic phrase
+        #[arg(long)]
+        mnemonic_file: PathBuf,
+
+        /// File containing the BIP-39 passphrase; no passphrase when omitted
+        #[arg(long)]
+        passphrase_file: Option<PathBuf>,
+
+        /// Number of accounts to list, starting at index 0 of m/44'/60'/0'/0/<INDEX>
+        #[arg(long, default_value = "10")]
+        count: u32,
+    },
+
+    /// Reassemble a payload from scanned QR-code (UR) frames and print it (no network calls)
+    QrDecode {
+        /// Scanned UR frame (e.g. "ur:bytes/1-3/..."); repeat for every frame of an animated code
+        #[arg(long = "frame", required_unless_present = "frames_file")]
+        frames: Vec<String>,
+
+        /// File with one scanned UR frame per line, or `-` to read them from stdin
+        #[arg(long, conflicts_with = "frames")]
+        frames_file: Option<PathBuf>,
+    },
+
     /// Output CLI documentation in Markdown format
     MarkdownHelp,
 }
 
+#[derive(Args)]
+struct QrArgs {
+    /// Also show the output as a QR code on stderr, animated (multi-part UR) when it is large
+    #[arg(long)]
+    qr: bool,
+
+    /// Also write the output as a QR code PNG; animated codes get one `<NAME>-<N>.png` per frame
+    #[arg(long)]
+    qr_png: Option<PathBuf>,
+
+    /// Maximum payload bytes per QR frame before splitting into an animated multi-part UR
+    #[arg(long, default_value_t = lib::qr::DEFAULT_FRAGMENT_LEN)]
+    qr_fragment_len: usize,
+
+    /// Delay between the frames of an animated terminal QR code, in milliseconds
+    #[arg(long, default_value = "500")]
+    qr_frame_ms: u64,
+
+    /// Stop an animated terminal QR code after this many passes over its frames (default:
+    /// when Enter is pressed, or after one pass if stdin is not a terminal)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    qr_loops: Option<u32>,
+}
+
+impl QrArgs {
+    /// Render `data` as requested; an animated terminal code loops until Enter is pressed or
+    /// `--qr-loops` passes are shown.
+    fn show(&self, data: &[u8]) -> eyre::Result<()> {
+        if !self.qr && self.qr_png.is_none() {
+            return Ok(());
+        }
+        let frames = lib::qr::encode_ur(data, self.qr_fragment_len)?;
+        if let Some(path) = &self.qr_png {
+            if let [frame] = frames.as_slice() {
+                lib::qr::write_png(frame, 8, path)?;
+                eprintln!("QR code written to {}", path.display());
+            } else {
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                for (index, frame) in frames.iter().enumerate() {
+                    let path = path.with_file_name(format!("{stem}-{}.png", index + 1));
+                    lib::qr::write_png(frame, 8, path)?;
+                }
+                eprintln!("{} QR frames written next to {}", frames.len(), path.display());
+            }
+        }
+        if !self.qr {
+            return Ok(());
+        }
+        if let [frame] = frames.as_slice() {
+            eprintln!("{}", lib::qr::render_terminal(frame)?);
+            return Ok(());
+        }
+        let rendered = frames
+            .iter()
+            .map(|frame| lib::qr::render_terminal(frame))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (stop_tx, stop) = mpsc::channel();
+        let interactive = std::io::stdin().is_terminal();
+        if interactive {
+            std::thread::spawn(move || {
+                let _ = std::io::stdin().read_line(&mut String::new());
+                let _ = stop_tx.send(());
+            });
+        }
+        let passes = self.qr_loops.or((!interactive).then_some(1));
+        let limit = passes.map(|passes| passes as usize * rendered.len());
+        let frames = rendered.iter().enumerate().cycle().enumerate();
+        for (shown, (index, qr)) in frames {
+            if limit.is_some_and(|limit| shown >= limit) {
+                break;
+            }
+            let hint = if interactive { "; press Enter once the scanner is done" } else { "" };
+            // Clear the screen and redraw from the top-left corner.
+            eprint!("\x1b[2J\x1b[H{qr}\nFrame {}/{}{hint}", index + 1, rendered.len());
+            // The sender is kept alive without a terminal, so this always waits the full delay.
+            if stop.recv_timeout(Duration::from_millis(self.qr_frame_ms)).is_ok() {
+                break;
+            }
+        }
+        eprintln!();
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+struct KeyArgs {
+    /// 0x-prefixed private key for signing
+    #[arg(
+        long,
+        env = "PRIVATE_KEY",
+        required_unless_present_any = ["keystore", "mnemonic_file", "remote_signer"]
+    )]
+    private_key: Option<PrivateKeySigner>,
+
+    /// Path to an encrypted V3 keystore JSON file (scrypt or pbkdf2); overrides `--private-key`
+    #[arg(long, conflicts_with = "mnemonic_file")]
+    keystore: Option<PathBuf>,
+
+    /// File containing the keystore password; prompted for on the terminal when omitted
+    #[arg(long, requires = "keystore")]
+    password_file: Option<PathBuf>,
+
+    /// File containing a BIP-39 mnemonic phrase; overrides `--private-key`
+    #[arg(long)]
+    mnemonic_file: Option<PathBuf>,
+
+    /// File containing the BIP-39 passphrase; no passphrase when omitted
+    #[arg(long, requires = "mnemonic_file")]
+    passphrase_file: Option<PathBuf>,
+
+    /// BIP-32 derivation path of the signing account (e.g. "m/44'/60'/0'/0/0")
+    #[arg(long, requires = "mnemonic_file", conflicts_with = "account_index")]
+    derivation_path: Option<String>,
+
+    /// Account index under the default derivation path m/44'/60'/0'/0/<INDEX>
+    #[arg(long, requires = "mnemonic_file")]
+    account_index: Option<u32>,
+
+    /// URL of a remote signing service (Clef, Web3Signer) holding the key; overrides `--private-key`
+    #[arg(long, requires = "from", conflicts_with_all = ["keystore", "mnemonic_file"])]
+    remote_signer: Option<url::Url>,
+
+    /// 0x-prefixed address of the account to sign with on the remote signer
+    #[arg(long, requires = "remote_signer")]
+    from: Option<Address>,
+
+    /// API of the remote signing service [default: json-rpc]
+    #[arg(long, value_enum, requires = "remote_signer")]
+    remote_api: Option<RemoteApi>,
+}
+
+impl KeyArgs {
+    /// Load the signing key, or connect to the remote signer if one is given.
+    fn backend(self) -> eyre::Result<Signer> {
+        match (&self.remote_signer, self.from) {
+            (Some(url), Some(from)) => {
+                let api = self.remote_api.unwrap_or(RemoteApi::JsonRpc).into();
+                Ok(Signer::Remote(lib::remote::RemoteSigner::new(url.clone(), from, api)))
+            }
+            (Some(_), None) => eyre::bail!("--from is required with --remote-signer"),
+            (None, _) => self.signer().map(Signer::Local),
+        }
+    }
+
+    /// Load the local signing key, decrypting the keystore or deriving from the mnemonic if given.
+    fn signer(self) -> eyre::Result<PrivateKeySigner> {
+        eyre::ensure!(self.remote_signer.is_none(), "remote signers can only sign transactions");
+        match (self.keystore, self.mnemonic_file, self.private_key) {
+            (Some(keystore), _, _) => {
+                let password = read_password(self.password_file, false)?;
+                Ok(lib::keystore::decrypt(keystore, password)?)
+            }
+            (None, Some(mnemonic_file), _) => {
+                let phrase = std::fs::read_to_string(mnemonic_file)?;
+                let passphrase = read_passphrase(self.passphrase_file)?;
+                let derivation_path = self.derivation_path.unwrap_or_else(|| {
+                    lib::mnemonic::account_path(self.account_index.unwrap_or_default())
+                });
+                Ok(lib::mnemonic::derive(phrase.trim(), &passphrase, &derivation_path)?)
+            }
+            (None, None, Some(private_key)) => Ok(private_key),
+            (None, None, None) => {
+                eyre::bail!("one of --private-key, --keystore or --mnemonic-file is required")
+            }
+        }
+    }
+}
+
+/// A signing key held locally or by a remote signing service.
+enum Signer {
+    Local(PrivateKeySigner),
+    Remote(lib::remote::RemoteSigner),
+}
+
+impl SignerBackend for Signer {
+    fn address(&self) -> Address {
+        match self {
+            Self::Local(signer) => signer.address(),
+            Self::Remote(signer) => SignerBackend::address(signer),
+        }
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> Result<Signature, lib::sign::Error> {
+        match self {
+            Self::Local(signer) => SignerBackend::sign_transaction(signer, tx).await,
+            Self::Remote(signer) => signer.sign_transaction(tx).await,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum KeystoreCommand {
+    /// Generate a random key, save it as an encrypted keystore and print its address
+    New {
+        /// Directory to write the keystore file into (created if missing)
+        #[arg(long)]
+        dir: PathBuf,
+
+        /// Keystore file name (a random UUID when omitted)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// File containing the new password; prompted for twice on the terminal when omitted
+        #[arg(long)]
+        password_file: Option<PathBuf>,
+    },
+
+    /// Encrypt an existing private key into a keystore and print its address
+    Import {
+        /// 0x-prefixed private key to encrypt
+        #[arg(long, env = "PRIVATE_KEY")]
+        private_key: PrivateKeySigner,
+
+        /// Directory to write the keystore file into (created if missing)
+        #[arg(long)]
+        dir: PathBuf,
+
+        /// Keystore file name (a random UUID when omitted)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// File containing the new password; prompted for twice on the terminal when omitted
+        #[arg(long)]
+        password_file: Option<PathBuf>,
+    },
+
+    /// Decrypt a keystore and print its 0x-prefixed private key
+    Export {
+        /// Path to the encrypted V3 keystore JSON file
+        #[arg(long)]
+        keystore: PathBuf,
+
+        /// File containing the keystore password; prompted for on the terminal when omitted
+        #[arg(long)]
+        password_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SignMode {
+    #[command(flatten)]
+    Raw(UniqueArgs),
+
+    /// Call a contract function, ABI-encoding the calldata offline
+    Call {
+        /// Human-readable function signature (e.g. "transfer(address,uint256)")
+        #[arg(long, required_unless_present = "abi", conflicts_with = "abi")]
+        sig: Option<String>,
+
+        /// Path to a JSON ABI or compiler artifact containing an `abi` field
+        #[arg(long, requires = "function")]
+        abi: Option<PathBuf>,
+
+        /// Name of the function to call in the JSON ABI, or its full signature (e.g.
+        /// "set(uint256,bool)") to pick one of several overloads with the same argument count
+        #[arg(long, requires = "abi")]
+        function: Option<String>,
+
+        /// Function argument in Solidity literal syntax; repeat in parameter order
+        #[arg(long = "arg", allow_hyphen_values = true)]
+        args: Vec<String>,
+
+        /// Specify fee model and parameters
+        #[command(subcommand)]
+        unique_args: UniqueArgs,
+    },
+
+    /// Transfer ERC-20 tokens; `--to` and `--eth` are derived (token address, zero value)
+    Erc20Transfer {
+        /// 0x-prefixed ERC-20 token contract address
+        #[arg(long)]
+        token: Address,
+
+        /// 0x-prefixed address receiving the tokens
+        #[arg(long)]
+        recipient: Address,
+
+        /// Amount in token units (e.g. "12.5")
+        #[arg(long)]
+        amount: String,
+
+        /// Number of decimals of the token (e.g. 6 for USDC, 18 for DAI)
+        #[arg(long)]
+        decimals: u8,
+
+        /// Specify fee model and parameters
+        #[command(subcommand)]
+        unique_args: UniqueArgs,
+    },
+
+    /// Approve an ERC-20 allowance; `--to` and `--eth` are derived (token address, zero value)
+    Erc20Approve {
+        /// 0x-prefixed ERC-20 token contract address
+        #[arg(long)]
+        token: Address,
+
+        /// 0x-prefixed address allowed to spend the tokens
+        #[arg(long)]
+        spender: Address,
+
+        /// Allowance in token units (e.g. "12.5")
+        #[arg(long)]
+        amount: String,
+
+        /// Number of decimals of the token (e.g. 6 for USDC, 18 for DAI)
+        #[arg(long)]
+        decimals: u8,
+
+        /// Specify fee model and parameters
+        #[command(subcommand)]
+        unique_args: UniqueArgs,
+    },
+
+    /// Deploy a contract (CREATE) and print its address to stderr
+    Deploy {
+        /// File containing hex-encoded contract init-code (creation bytecode)
+        #[arg(long)]
+        bytecode: PathBuf,
+
+        /// Hex-encoded ABI-encoded constructor arguments, appended to the init-code
+        #[arg(long)]
+        constructor_args: Option<Bytes>,
+
+        /// Specify fee model and parameters
+        #[command(subcommand)]
+        unique_args: UniqueArgs,
+    },
+
+    /// Speed up the pending transaction given by `--original-hex` or `--original-file`: re-sign
+    /// it with the same nonce and bumped fees
+    Replace(BumpArgs),
+
+    /// Cancel the pending transaction given by `--original-hex` or `--original-file`: a
+    /// zero-value self-transfer with its nonce and bumped fees
+    Cancel(BumpArgs),
+}
+
+/// The pending transaction for `replace` or `cancel`; it supplies the chain ID, nonce, gas
+/// limit and fee model.
+#[derive(Args)]
+struct OriginalArgs {
+    /// Signed and EIP-2718-encoded hex of the pending transaction to replace or cancel
+    /// (without `0x` prefix)
+    #[arg(
+        long,
+        conflicts_with_all = ["chain_id", "nonce", "gas_limit", "to", "eth", "data", "data_file", "from_file"]
+    )]
+    original_hex: Option<String>,
+
+    /// File containing the hex or signed envelope of the pending transaction to replace or cancel
+    #[arg(
+        long,
+        conflicts_with_all = ["original_hex", "chain_id", "nonce", "gas_limit", "to", "eth", "data", "data_file", "from_file"]
+    )]
+    original_file: Option<PathBuf>,
+}
+
+/// Fee increase over the pending transaction, as a percentage or as explicit fees.
+#[derive(Args)]
+struct BumpArgs {
+    /// Percentage to raise every fee by; nodes require at least 10 (100 for blob transactions)
+    #[arg(long, default_value_t = lib::replace::MIN_BUMP_PERCENT)]
+    bump_percent: u32,
+
+    /// New gas price in Wei for legacy and EIP-2930 transactions, instead of the bump
+    #[arg(long)]
+    gas_price: Option<u128>,
+
+    /// New maximum total fee per gas in Wei, instead of the bump
+    #[arg(long)]
+    max_fee_per_gas: Option<u128>,
+
+    /// New maximum priority fee per gas in Wei, instead of the bump
+    #[arg(long)]
+    max_priority_fee_per_gas: Option<u128>,
+
+    /// New maximum fee per blob gas in Wei for EIP-4844 transactions, instead of the bump
+    #[arg(long)]
+    max_fee_per_blob_gas: Option<u128>,
+}
+
+impl BumpArgs {
+    /// Read the original transaction and build its replacement, signed by the original sender.
+    fn replacement(
+        self,
+        original: OriginalArgs,
+        cancel: bool,
+    ) -> eyre::Result<(lib::UnsignedTx, Address)> {
+        eyre::ensure!(
+            original.original_hex.is_some() || original.original_file.is_some(),
+            "replace and cancel need --original-hex or --original-file"
+        );
+        let (original, _) = read_signed(None, original.original_hex, original.original_file)?;
+        let overrides = lib::replace::FeeOverrides {
+            gas_price: self.gas_price,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+            max_fee_per_blob_gas: self.max_fee_per_blob_gas,
+        };
+        let build = if cancel { lib::replace::cancel } else { lib::replace::replace };
+        Ok(build(&original, self.bump_percent, &overrides)?)
+    }
+}
+
 #[derive(Subcommand)]
 enum UniqueArgs {
+    /// Use the legacy gas price model
+    Legacy {
+        /// Gas price in Wei
+        #[arg(long)]
+        gas_price: u128,
+    },
+
+    /// Use the EIP-2930 access list model
+    Eip2930 {
+        /// Gas price in Wei
+        #[arg(long)]
+        gas_price: u128,
+
+        /// Path to a JSON access list (`[{"address": ..., "storageKeys": [...]}]`)
+        #[arg(long, value_parser = read_access_list)]
+        access_list: AccessList,
+    },
+
     /// Use the EIP-1559 fee market model
     Eip1559 {
         /// Maximum total fee per gas in Wei
@@ -93,22 +762,367 @@ enum UniqueArgs {
         /// Maximum priority fee per gas (tip) in Wei
         #[arg(long)]
         max_priority_fee_per_gas: u128,
+
+        /// Path to a JSON access list (`[{"address": ..., "storageKeys": [...]}]`)
+        #[arg(long, value_parser = read_access_list)]
+        access_list: Option<AccessList>,
     },
 
-    /// Use the legacy gas price model
-    Legacy {
-        /// Gas price in Wei
+    /// Use the EIP-4844 blob transaction model
+    Eip4844 {
+        /// Maximum total fee per gas in Wei
         #[arg(long)]
-        gas_price: u128,
+        max_fee_per_gas: u128,
+
+        /// Maximum priority fee per gas (tip) in Wei
+        #[arg(long)]
+        max_priority_fee_per_gas: u128,
+
+        /// Maximum fee per blob gas in Wei
+        #[arg(long)]
+        max_fee_per_blob_gas: u128,
+
+        /// Path to a JSON access list (`[{"address": ..., "storageKeys": [...]}]`)
+        #[arg(long, value_parser = read_access_list)]
+        access_list: Option<AccessList>,
+
+        /// Path to a file of data to carry in blobs; repeat for more files (6 blobs at most)
+        #[arg(long = "blob", required = true)]
+        blobs: Vec<PathBuf>,
+    },
+
+    /// Use the EIP-7702 set-code transaction model
+    Eip7702 {
+        /// Maximum total fee per gas in Wei
+        #[arg(long)]
+        max_fee_per_gas: u128,
+
+        /// Maximum priority fee per gas (tip) in Wei
+        #[arg(long)]
+        max_priority_fee_per_gas: u128,
+
+        /// Path to a JSON access list (`[{"address": ..., "storageKeys": [...]}]`)
+        #[arg(long, value_parser = read_access_list)]
+        access_list: Option<AccessList>,
+
+        /// Path to a JSON authorization produced by `sign-authorization`; repeat for multiple
+        #[arg(long = "authorization", required = true, value_parser = read_authorization)]
+        authorization_list: Vec<SignedAuthorization>,
     },
 }
 
-#[derive(ValueEnum, Clone)]
+#[derive(ValueEnum, Clone, Copy)]
 enum TxType {
-    /// Use the EIP-1559 fee market (Type-2 transaction)
-    Eip1559,
     /// Use the legacy gas price model (Type-0 transaction)
     Legacy,
+    /// Use the access list model (Type-1 transaction)
+    Eip2930,
+    /// Use the EIP-1559 fee market (Type-2 transaction)
+    Eip1559,
+    /// Use the blob transaction model (Type-3 transaction)
+    Eip4844,
+    /// Use the set-code transaction model (Type-4 transaction)
+    Eip7702,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum RemoteApi {
+    /// `eth_signTransaction` JSON-RPC (Clef, Web3Signer Eth1 proxy)
+    JsonRpc,
+    /// Web3Signer REST `/api/v1/eth1/sign/{address}` endpoint
+    #[value(name = "web3signer")]
+    Web3Signer,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum FailurePolicy {
+    /// Skip the failing sender's remaining transactions, whose nonces cannot be mined after a gap
+    Stop,
+    /// Submit the remaining transactions anyway
+    Continue,
+}
+
+/// What `confirm --revert-reason` learned about a reverted transaction.
+enum Replay {
+    /// No replay: not requested, not reverted, or the replay failed.
+    Skipped,
+    /// The replay on the parent block succeeded, so the reason is unknown.
+    DidNotRevert,
+    /// The replay reverted with this reason.
+    Reason(lib::revert::RevertReason),
+}
+
+impl From<FailurePolicy> for lib::batch::FailurePolicy {
+    fn from(policy: FailurePolicy) -> Self {
+        match policy {
+            FailurePolicy::Stop => Self::Stop,
+            FailurePolicy::Continue => Self::Continue,
+        }
+    }
+}
+
+impl From<RemoteApi> for lib::remote::RemoteApi {
+    fn from(api: RemoteApi) -> Self {
+        match api {
+            RemoteApi::JsonRpc => Self::JsonRpc,
+            RemoteApi::Web3Signer => Self::Web3Signer,
+        }
+    }
+}
+
+impl From<TxType> for alloy::consensus::TxType {
+    fn from(tx_type: TxType) -> Self {
+        match tx_type {
+            TxType::Legacy => Self::Legacy,
+            TxType::Eip2930 => Self::Eip2930,
+            TxType::Eip1559 => Self::Eip1559,
+            TxType::Eip4844 => Self::Eip4844,
+            TxType::Eip7702 => Self::Eip7702,
+        }
+    }
+}
+
+/// Read a password from the first line of a file, or prompt for it on the terminal.
+///
+/// When `confirm` is set, a prompted password must be entered twice.
+fn read_password(password_file: Option<PathBuf>, confirm: bool) -> eyre::Result<String> {
+    if let Some(path) = password_file {
+        return read_first_line(path);
+    }
+    let password = rpassword::prompt_password("Keystore password: ")?;
+    if confirm {
+        let repeated = rpassword::prompt_password("Repeat password: ")?;
+        eyre::ensure!(password == repeated, "passwords do not match");
+    }
+    Ok(password)
+}
+
+/// Read the BIP-39 passphrase from the first line of a file; empty if no file is given.
+fn read_passphrase(passphrase_file: Option<PathBuf>) -> eyre::Result<String> {
+    Ok(passphrase_file.map(read_first_line).transpose()?.unwrap_or_default())
+}
+
+/// Read the first line of a secret file, without its line terminator.
+fn read_first_line(path: PathBuf) -> eyre::Result<String> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(contents.lines().next().unwrap_or_default().to_owned())
+}
+
+/// Parse a duration such as `500ms`, `30s`, `5m` or `1h`; a bare number is in seconds.
+fn parse_duration(duration: &str) -> eyre::Result<Duration> {
+    let split = duration.find(|c: char| !c.is_ascii_digit()).unwrap_or(duration.len());
+    let (amount, unit) = duration.split_at(split);
+    let amount: u64 = amount.parse()?;
+    let seconds = |factor: u64| {
+        let seconds = amount.checked_mul(factor);
+        seconds
+            .map(Duration::from_secs)
+            .ok_or_else(|| eyre::eyre!("duration {duration:?} is too long"))
+    };
+    match unit.trim() {
+        "ms" => Ok(Duration::from_millis(amount)),
+        "" | "s" => Ok(Duration::from_secs(amount)),
+        "m" => seconds(60),
+        "h" => seconds(3600),
+        unit => eyre::bail!("unknown duration unit {unit:?}; use ms, s, m or h"),
+    }
+}
+
+/// Read and parse a JSON access list file.
+fn read_access_list(path: &str) -> eyre::Result<AccessList> {
+    let json = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Resolve calldata given inline, via a file, or via stdin (`-`); empty if none is given.
+fn read_calldata(data: Option<Bytes>, data_file: Option<PathBuf>) -> eyre::Result<Bytes> {
+    let hex = match (data, data_file) {
+        (Some(data), _) => return Ok(data),
+        (None, None) => return Ok(Bytes::new()),
+        (None, Some(path)) if path.as_os_str() == "-" => {
+            let mut hex = String::new();
+            std::io::stdin().read_to_string(&mut hex)?;
+            hex
+        }
+        (None, Some(path)) => std::fs::read_to_string(path)?,
+    };
+    Ok(hex.trim().parse()?)
+}
+
+/// Check that no conflicting transaction fields were given, print a summary of the
+/// ERC-20 call to stderr, and return its calldata.
+fn erc20_calldata(
+    call: &lib::erc20::Erc20Call,
+    token: Address,
+    decimals: u8,
+    to: Option<Address>,
+    value: Option<lib::Wei>,
+    data: &Option<Bytes>,
+    data_file: &Option<PathBuf>,
+) -> eyre::Result<Bytes> {
+    eyre::ensure!(
+        to.is_none() && value.is_none() && data.is_none() && data_file.is_none(),
+        "--to, --eth, --data and --data-file cannot be combined with ERC-20 modes"
+    );
+    eprintln!("{}", call.summary(token, decimals));
+    Ok(call.calldata())
+}
+
+/// Read and parse a JSON signed authorization file.
+fn read_authorization(path: &str) -> eyre::Result<SignedAuthorization> {
+    let json = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Decode the signed transaction given inline or via a file, checking its type if one is expected.
+///
+/// Input starting with `{` is read as a signed envelope, whose metadata is returned alongside.
+fn read_signed(
+    tx_type: Option<TxType>,
+    signed_hex: Option<String>,
+    signed_file: Option<PathBuf>,
+) -> eyre::Result<(lib::AnyTxSigned, lib::envelope::Metadata)> {
+    let signed_hex = match (signed_hex, signed_file) {
+        (Some(signed_hex), _) => signed_hex,
+        (None, Some(path)) => std::fs::read_to_string(path)?,
+        (None, None) => eyre::bail!("either --signed-hex or --signed-file is required"),
+    };
+    let (signed, metadata) = if signed_hex.trim_start().starts_with('{') {
+        let envelope = lib::Envelope::from_json(&signed_hex)?;
+        let metadata = envelope.metadata.clone();
+        (envelope.into_signed()?, metadata)
+    } else {
+        (lib::AnyTxSigned::decode_2718(&hex::decode(signed_hex.trim())?)?, Default::default())
+    };
+    if let Some(expected) = tx_type.map(alloy::consensus::TxType::from) {
+        let found = signed.tx_type();
+        eyre::ensure!(found == expected, "expected a {expected} transaction, found {found}");
+    }
+    Ok((signed, metadata))
+}
+
+/// Decode every signed transaction of a batch file: an envelope array, one envelope, or hex lines.
+fn read_signed_batch(path: PathBuf) -> eyre::Result<Vec<lib::AnyTxSigned>> {
+    let content = std::fs::read_to_string(path)?;
+    let content = content.trim_start();
+    if content.starts_with('[') {
+        let envelopes = lib::Envelope::list_from_json(content)?;
+        Ok(envelopes.into_iter().map(lib::Envelope::into_signed).collect::<Result<_, _>>()?)
+    } else if content.starts_with('{') {
+        Ok(vec![lib::Envelope::from_json(content)?.into_signed()?])
+    } else {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(lib::AnyTxSigned::decode_2718(&hex::decode(line.trim())?)?))
+            .collect()
+    }
+}
+
+/// Resolve the recipient, value, calldata and fee model of a signing mode.
+fn resolve_mode(
+    mode: SignMode,
+    to: Option<Address>,
+    value: Option<lib::Wei>,
+    data: Option<Bytes>,
+    data_file: Option<PathBuf>,
+) -> eyre::Result<(Option<Address>, lib::Wei, Bytes, UniqueArgs)> {
+    let required_value = || value.ok_or_else(|| eyre::eyre!("--eth is required"));
+    let resolved = match mode {
+        SignMode::Raw(unique_args) => {
+            let to = to.ok_or_else(|| eyre::eyre!("--to is required unless deploying"))?;
+            (Some(to), required_value()?, read_calldata(data, data_file)?, unique_args)
+        }
+        SignMode::Call { sig, abi, function, args, unique_args } => {
+            let to = to.ok_or_else(|| eyre::eyre!("--to is required for call"))?;
+            eyre::ensure!(
+                data.is_none() && data_file.is_none(),
+                "--data and --data-file cannot be combined with call"
+            );
+            let input = match (sig, abi, function) {
+                (Some(sig), _, _) => {
+                    lib::abi::encode_call(&lib::abi::parse_signature(&sig)?, &args)?
+                }
+                (None, Some(abi), Some(function)) => {
+                    let abi = lib::abi::parse_json_abi(&std::fs::read_to_string(abi)?)?;
+                    let function = lib::abi::find_function(&abi, &function, args.len())?;
+                    lib::abi::encode_call(function, &args)?
+                }
+                _ => eyre::bail!("either --sig or --abi with --function is required"),
+            };
+            (Some(to), required_value()?, input, unique_args)
+        }
+        SignMode::Erc20Transfer { token, recipient, amount, decimals, unique_args } => {
+            let amount = lib::erc20::parse_amount(&amount, decimals)?;
+            let call = lib::erc20::Erc20Call::Transfer { recipient, amount };
+            let input = erc20_calldata(&call, token, decimals, to, value, &data, &data_file)?;
+            (Some(token), lib::Wei::ZERO, input, unique_args)
+        }
+        SignMode::Erc20Approve { token, spender, amount, decimals, unique_args } => {
+            let amount = lib::erc20::parse_amount(&amount, decimals)?;
+            let call = lib::erc20::Erc20Call::Approve { spender, amount };
+            let input = erc20_calldata(&call, token, decimals, to, value, &data, &data_file)?;
+            (Some(token), lib::Wei::ZERO, input, unique_args)
+        }
+        SignMode::Deploy { bytecode, constructor_args, unique_args } => {
+            eyre::ensure!(
+                to.is_none() && data.is_none() && data_file.is_none(),
+                "--to, --data and --data-file cannot be combined with deploy"
+            );
+            let init_code = read_calldata(None, Some(bytecode))?;
+            let input = [init_code, constructor_args.unwrap_or_default()].concat();
+            (None, required_value()?, input.into(), unique_args)
+        }
+        SignMode::Replace(_) | SignMode::Cancel(_) => {
+            eyre::bail!("replace and cancel reuse the original transaction")
+        }
+    };
+    Ok(resolved)
+}
+
+/// Convert the chosen fee model and parameters into a type-specific payload.
+fn unique_payload(unique_args: UniqueArgs) -> eyre::Result<UniquePayload> {
+    let unique_payload = match unique_args {
+        UniqueArgs::Legacy { gas_price } => UniquePayload::Legacy(LegacyPayload { gas_price }),
+        UniqueArgs::Eip2930 { gas_price, access_list } => {
+            UniquePayload::Eip2930(Eip2930Payload { gas_price, access_list })
+        }
+        UniqueArgs::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas, access_list } => {
+            UniquePayload::Eip1559(Eip1559Payload {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                access_list: access_list.unwrap_or_default(),
+            })
+        }
+        UniqueArgs::Eip4844 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            access_list,
+            blobs,
+        } => {
+            let blobs = blobs.iter().map(std::fs::read).collect::<Result<Vec<_>, _>>()?;
+            UniquePayload::Eip4844(Eip4844Payload {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
+                access_list: access_list.unwrap_or_default(),
+                sidecar: lib::sign::blob_sidecar(blobs)?,
+            })
+        }
+        UniqueArgs::Eip7702 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list,
+            authorization_list,
+        } => UniquePayload::Eip7702(Eip7702Payload {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list: access_list.unwrap_or_default(),
+            authorization_list,
+        }),
+    };
+    Ok(unique_payload)
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -119,45 +1133,344 @@ async fn main() -> eyre::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Command::Sign { private_key, chain_id, nonce, gas_limit, to, value, unique_args } => {
-            let common_payload = lib::CommonPayload { chain_id, nonce, gas_limit, to, value };
-            let signed_hex = match unique_args {
-                UniqueArgs::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
-                    let unique_payload =
-                        Eip1559Payload { max_fee_per_gas, max_priority_fee_per_gas };
-                    let signed_bytes: TxEip2718Bytes<TxEip1559> =
-                        common_payload.build(unique_payload).sign(&private_key)?.encode_2718();
-                    hex::encode(signed_bytes)
-                }
-                UniqueArgs::Legacy { gas_price } => {
-                    let unique_payload = LegacyPayload { gas_price };
-                    let signed_bytes: TxEip2718Bytes<TxLegacy> =
-                        common_payload.build(unique_payload).sign(&private_key)?.encode_2718();
-                    hex::encode(signed_bytes)
+        Command::Sign {
+            key,
+            chain_id,
+            nonce,
+            gas_limit,
+            to,
+            value,
+            data,
+            data_file,
+            from_file,
+            original,
+            envelope,
+            output,
+            description,
+            qr,
+            mode,
+        } => {
+            let mut prepared_description = None;
+            let (tx, expected_sender) = match (from_file, mode) {
+                (Some(from_file), None) => {
+                    let prepared = lib::Envelope::from_json(&std::fs::read_to_string(from_file)?)?;
+                    prepared_description = prepared.metadata.description.clone();
+                    let sender = prepared.sender;
+                    (prepared.into_unsigned()?, sender)
                 }
+                (None, Some(SignMode::Replace(bump))) => {
+                    let (tx, sender) = bump.replacement(original, false)?;
+                    (tx, Some(sender))
+                }
+                (None, Some(SignMode::Cancel(bump))) => {
+                    let (tx, sender) = bump.replacement(original, true)?;
+                    (tx, Some(sender))
+                }
+                (None, Some(mode)) => {
+                    // Clap requires both unless an original transaction is given.
+                    let (Some(chain_id), Some(nonce)) = (chain_id, nonce) else {
+                        eyre::bail!(
+                            "--original-hex and --original-file are only used by replace and cancel"
+                        );
+                    };
+                    let (to, value, input, unique_args) =
+                        resolve_mode(mode, to, value, data, data_file)?;
+                    let common =
+                        lib::CommonPayload { chain_id, nonce, gas_limit, to, value, input };
+                    (lib::UnsignedTx { common, unique: unique_payload(unique_args)? }, None)
+                }
+                (Some(_), Some(_)) => {
+                    eyre::bail!("--from-file cannot be combined with a fee model or signing mode")
+                }
+                (None, None) => eyre::bail!(
+                    "a fee model or signing mode is required unless --from-file is given"
+                ),
+            };
+            eyre::ensure!(
+                description.is_none() || envelope || output == OutputFormat::Json,
+                "--description is only recorded with --envelope or --output json"
+            );
+            let signer = key.backend()?;
+            if let Some(expected_sender) = expected_sender {
+                eyre::ensure!(
+                    expected_sender == signer.address(),
+                    "the transaction is meant for {expected_sender}, but the key belongs to {}",
+                    signer.address()
+                );
+            }
+            let contract_address = tx.common.contract_address(signer.address());
+            let signed = tx.sign_async(&signer).await?;
+            let metadata = lib::envelope::Metadata::new(description.or(prepared_description));
+            let qr_payload = if output == OutputFormat::Json {
+                let output = lib::output::SignOutput::new(
+                    signed,
+                    signer.address(),
+                    contract_address,
+                    metadata,
+                )?;
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                output.envelope.to_json()?.into_bytes()
+            } else if envelope {
+                let payload = lib::envelope::Payload::Signed(signed.encode_2718());
+                let json =
+                    lib::Envelope::new(payload, Some(signer.address()), metadata)?.to_json()?;
+                println!("{json}");
+                json.into_bytes()
+            } else {
+                let signed = signed.encode_2718();
+                println!("{}", hex::encode(&signed));
+                signed.as_ref().to_vec()
             };
-            println!("{signed_hex}")
-        }
-        Command::Submit { signed_hex, rpc_url, tx_type } => {
-            let tx_hash = match tx_type {
-                TxType::Eip1559 => {
-                    let signed_bytes: TxEip2718Bytes<TxEip1559> =
-                        hex::decode(signed_hex).map(TxEip2718Bytes::from_untyped)?;
-                    let signed = signed_bytes.decode_2718()?;
-                    signed.submit(rpc_url).await?
-                }
-                TxType::Legacy => {
-                    let signed_bytes: TxEip2718Bytes<TxLegacy> =
-                        hex::decode(signed_hex).map(TxEip2718Bytes::from_untyped)?;
-                    let signed = signed_bytes.decode_2718()?;
-                    signed.submit(rpc_url).await?
+            if let Some(contract_address) = contract_address {
+                eprintln!("Contract address: {contract_address}");
+            }
+            qr.show(&qr_payload)?;
+        }
+        Command::Prepare {
+            from,
+            to,
+            value,
+            data,
+            data_file,
+            legacy,
+            out_file,
+            description,
+            qr,
+            rpc_url,
+        } => {
+            let input = read_calldata(data, data_file)?;
+            let fee_model = if legacy {
+                lib::prepare::FeeModel::Legacy
+            } else {
+                lib::prepare::FeeModel::Eip1559
+            };
+            let tx = lib::prepare::prepare(rpc_url, from, to, value, input, fee_model).await?;
+            let common = tx.common.clone();
+            let metadata = lib::envelope::Metadata::new(description);
+            let envelope = lib::Envelope::new(
+                lib::envelope::Payload::Unsigned(Box::new(tx)),
+                Some(from),
+                metadata,
+            )?;
+            let json = envelope.to_json()?;
+            std::fs::write(&out_file, &json)?;
+            eprintln!(
+                "Prepared nonce {} on chain {} with gas limit {}; written to {}",
+                common.nonce,
+                common.chain_id,
+                common.gas_limit,
+                out_file.display()
+            );
+            qr.show(json.as_bytes())?;
+        }
+        Command::SignBatch { key, chain_id, nonce, gas_limit, manifest, unique_args } => {
+            let manifest = if manifest.as_os_str() == "-" {
+                let mut manifest = String::new();
+                std::io::stdin().read_to_string(&mut manifest)?;
+                manifest
+            } else {
+                std::fs::read_to_string(manifest)?
+            };
+            let payments = lib::batch::parse_manifest(&manifest)?;
+            let template = lib::batch::BatchTemplate {
+                chain_id,
+                start_nonce: nonce,
+                gas_limit,
+                unique: unique_payload(unique_args)?,
+            };
+            let signer = key.backend()?;
+            let envelopes = lib::batch::sign_batch(&template, &payments, &signer).await?;
+            println!("{}", serde_json::to_string_pretty(&envelopes)?);
+            eprint!("{}", lib::batch::Summary::new(&template.transactions(&payments)));
+        }
+        Command::SignAuthorization { key, chain_id, address, nonce } => {
+            let authorization = Authorization { chain_id: U256::from(chain_id), address, nonce };
+            let signed = lib::sign::sign_authorization(authorization, &key.signer()?)?;
+            println!("{}", serde_json::to_string(&signed)?);
+        }
+        Command::Submit { signed_hex, signed_file, output, rpc_url, tx_type } => {
+            let (signed, _) = read_signed(tx_type, signed_hex, signed_file)?;
+            // Only JSON output needs the sender and nonce; text output submits as is.
+            let inspection = match output {
+                OutputFormat::Json => Some(signed.inspect()?),
+                OutputFormat::Text => None,
+            };
+            let hash = signed.submit(rpc_url).await?;
+            match inspection {
+                Some(inspection) => {
+                    let output = lib::output::SubmitOutput {
+                        hash,
+                        sender: inspection.sender,
+                        nonce: inspection.nonce,
+                    };
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                }
+                None => println!("{hash}"),
+            }
+        }
+        Command::SubmitBatch { signed_files, on_failure, rpc_url } => {
+            let mut txs = Vec::new();
+            for path in signed_files {
+                txs.extend(read_signed_batch(path)?);
+            }
+            let count = txs.len();
+            let report = lib::batch::submit_batch(txs, rpc_url, on_failure.into()).await?;
+            print!("{report}");
+            let unsubmitted = report.unsubmitted();
+            eyre::ensure!(
+                unsubmitted == 0,
+                "{unsubmitted} of {count} transactions were not submitted"
+            );
+        }
+        Command::Inspect { tx_type, signed_hex, signed_file } => {
+            let (signed, metadata) = read_signed(tx_type, signed_hex, signed_file)?;
+            if let Some(description) = metadata.description {
+                println!("Description: {description}");
+            }
+            print!("{}", signed.inspect()?);
+        }
+        Command::Confirm {
+            tx_hash,
+            confirmations,
+            timeout,
+            poll_interval,
+            revert_reason,
+            abis,
+            output,
+            rpc_url,
+        } => {
+            let abis = abis
+                .into_iter()
+                .map(|path| Ok(lib::abi::parse_json_abi(&std::fs::read_to_string(path)?)?))
+                .collect::<eyre::Result<Vec<_>>>()?;
+            let options = lib::confirm::WaitOptions {
+                confirmations,
+                timeout: (!timeout.is_zero()).then_some(timeout),
+                poll_interval,
+            };
+            let confirmation = match lib::confirm::wait_for_confirmations(
+                tx_hash,
+                rpc_url.clone(),
+                &options,
+            )
+            .await
+            {
+                Ok(confirmation) => confirmation,
+                Err(error) => {
+                    let outcome = lib::confirm::Outcome::of_error(&error);
+                    eprintln!("Error: {:?}", eyre::Report::new(error));
+                    std::process::exit(outcome.exit_code().into());
+                }
+            };
+            let outcome = lib::confirm::Outcome::of(&confirmation);
+            let decoder = lib::logs::LogDecoder::new(&abis);
+            let replay = if revert_reason && outcome == lib::confirm::Outcome::Reverted {
+                match lib::revert::replay(tx_hash, rpc_url, &abis).await {
+                    Ok(Some(reason)) => Replay::Reason(reason),
+                    Ok(None) => Replay::DidNotRevert,
+                    Err(error) => {
+                        eprintln!("Could not replay the transaction: {error}");
+                        Replay::Skipped
+                    }
                 }
+            } else {
+                Replay::Skipped
             };
-            println!("{tx_hash}");
+            if output == OutputFormat::Json {
+                let mut output = lib::output::ConfirmOutput::new(tx_hash, confirmation, &decoder);
+                if let Replay::Reason(reason) = replay {
+                    output.revert_reason = Some(reason);
+                }
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                match &confirmation {
+                    lib::confirm::Confirmation::Confirmed(receipt) => println!("{receipt:#?}"),
+                    lib::confirm::Confirmation::Reincluded { receipt, orphaned_block } => {
+                        eprintln!(
+                            "Reorg: block {orphaned_block} was orphaned; transaction re-included"
+                        );
+                        println!("{receipt:#?}");
+                    }
+                    lib::confirm::Confirmation::Dropped { orphaned_block } => eprintln!(
+                        "Transaction {tx_hash} was dropped: a reorg orphaned block {orphaned_block}"
+                    ),
+                    lib::confirm::Confirmation::Replaced { sender, nonce } => eprintln!(
+                        "Transaction {tx_hash} was replaced: nonce {nonce} of {sender} is used by \
+                         another transaction"
+                    ),
+                }
+                let logs =
+                    confirmation.receipt().into_iter().flat_map(|receipt| receipt.inner.logs());
+                for (position, log) in logs.enumerate() {
+                    if position == 0 {
+                        println!("Events:");
+                    }
+                    match decoder.decode(log) {
+                        Some(event) => println!("  {}: {event}", log.address()),
+                        None => println!(
+                            "  {}: unknown event {}",
+                            log.address(),
+                            log.topics().first().map_or("(anonymous)".into(), |t| t.to_string())
+                        ),
+                    }
+                }
+                if outcome == lib::confirm::Outcome::Reverted {
+                    eprintln!("Transaction {tx_hash} reverted");
+                    match replay {
+                        Replay::Reason(reason) => println!("Revert reason: {reason}"),
+                        Replay::DidNotRevert => {
+                            println!("Revert reason: unknown, the replay did not revert")
+                        }
+                        Replay::Skipped => {}
+                    }
+                }
+            }
+            if outcome != lib::confirm::Outcome::Success {
+                std::process::exit(outcome.exit_code().into());
+            }
+        }
+        Command::Keystore { command } => match command {
+            KeystoreCommand::New { dir, name, password_file } => {
+                let password = read_password(password_file, true)?;
+                let (signer, path) = lib::keystore::create(dir, password, name.as_deref())?;
+                println!("{}", signer.address());
+                eprintln!("Keystore written to {}", path.display());
+            }
+            KeystoreCommand::Import { private_key, dir, name, password_file } => {
+                let password = read_password(password_file, true)?;
+                let path = lib::keystore::import(dir, &private_key, password, name.as_deref())?;
+                println!("{}", private_key.address());
+                eprintln!("Keystore written to {}", path.display());
+            }
+            KeystoreCommand::Export { keystore, password_file } => {
+                let password = read_password(password_file, false)?;
+                println!("{}", lib::keystore::decrypt(keystore, password)?.to_bytes());
+            }
+        },
+        Command::Addresses { mnemonic_file, passphrase_file, count } => {
+            let phrase = std::fs::read_to_string(mnemonic_file)?;
+            let passphrase = read_passphrase(passphrase_file)?;
+            for (path, address) in lib::mnemonic::addresses(phrase.trim(), &passphrase, count)? {
+                println!("{path}\t{address}");
+            }
         }
-        Command::Confirm { tx_hash, rpc_url } => {
-            let receipt = lib::get_receipt(tx_hash, rpc_url).await?;
-            println!("{receipt:#?}");
+        Command::QrDecode { frames, frames_file } => {
+            let frames = match frames_file {
+                Some(path) if path.as_os_str() == "-" => {
+                    let mut frames = String::new();
+                    std::io::stdin().read_to_string(&mut frames)?;
+                    frames
+                }
+                Some(path) => std::fs::read_to_string(path)?,
+                None => frames.join("\n"),
+            };
+            let payload = lib::qr::decode_ur(frames.lines())?;
+            // Envelopes are JSON text; raw signed transactions are printed as hex for `submit`.
+            if payload.first() == Some(&b'{') {
+                println!("{}", String::from_utf8(payload)?);
+            } else {
+                println!("{}", hex::encode(payload));
+            }
         }
         Command::MarkdownHelp => clap_markdown::print_help_markdown::<Cli>(),
     }
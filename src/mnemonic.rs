@@ -0,0 +1,85 @@
+use super::*;
+use alloy::primitives::Address;
+use alloy::signers::local::{MnemonicBuilder, PrivateKeySigner, coins_bip39::English};
+
+/// BIP-44 derivation path prefix for Ethereum accounts; the account index is appended.
+pub const DEFAULT_DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0/";
+
+/// Errors for BIP-39 mnemonic key derivation.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to derive a key from the mnemonic: {0}
+    Derive(eyre::Report),
+}
+
+/// Derivation path of account `index` under [`DEFAULT_DERIVATION_PATH_PREFIX`].
+pub fn account_path(index: u32) -> String {
+    format!("{DEFAULT_DERIVATION_PATH_PREFIX}{index}")
+}
+
+/// Derive the signer at BIP-32 `derivation_path` from an English BIP-39 `phrase`.
+///
+/// `passphrase` is the optional BIP-39 passphrase; pass `""` if the wallet has none.
+///
+/// # Errors
+///
+/// Returns [`Error::Derive`] if the phrase is not a valid English mnemonic or the
+/// derivation path is malformed.
+pub fn derive(
+    phrase: &str,
+    passphrase: &str,
+    derivation_path: &str,
+) -> Result<PrivateKeySigner, Error> {
+    MnemonicBuilder::<English>::default()
+        .phrase(phrase)
+        .password(passphrase)
+        .derivation_path(derivation_path)
+        .and_then(|builder| builder.build())
+        .map_err(|e| Error::Derive(eyre::eyre!(e)))
+}
+
+/// List the derivation paths and addresses of the first `count` accounts of a mnemonic.
+///
+/// # Errors
+///
+/// Returns [`Error::Derive`] if the phrase is not a valid English mnemonic.
+pub fn addresses(
+    phrase: &str,
+    passphrase: &str,
+    count: u32,
+) -> Result<Vec<(String, Address)>, Error> {
+    (0..count)
+        .map(|index| {
+            let path = account_path(index);
+            let address = derive(phrase, passphrase, &path)?.address();
+            Ok((path, address))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    const PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn derives_default_accounts() -> eyre::Result<()> {
+        let accounts = addresses(PHRASE, "", 2)?;
+        assert_eq!(accounts[0].0, "m/44'/60'/0'/0/0");
+        assert_eq!(accounts[0].1, address!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+        assert_eq!(accounts[1].1, address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"));
+        assert_eq!(derive(PHRASE, "", &account_path(1))?.address(), accounts[1].1);
+        Ok(())
+    }
+
+    #[test]
+    fn passphrase_changes_derived_account() -> eyre::Result<()> {
+        let plain = derive(PHRASE, "", &account_path(0))?;
+        let protected = derive(PHRASE, "TREZOR", &account_path(0))?;
+        assert_ne!(plain.address(), protected.address());
+        assert!(matches!(derive("not a mnemonic", "", &account_path(0)), Err(Error::Derive(_))));
+        Ok(())
+    }
+}
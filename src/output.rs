@@ -0,0 +1,184 @@
+use super::*;
+use alloy::primitives::{Address, B256, TxHash};
+use alloy::rpc::types::eth::TransactionReceipt;
+use confirm::Confirmation;
+
+/// `sign --output json`: the signed envelope with the fields scripts look up most.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignOutput {
+    /// Transaction hash; for EIP-4844 it covers the transaction without its blob sidecar.
+    pub hash: TxHash,
+    /// Account that signed the transaction.
+    pub sender: Address,
+    /// Address of the deployed contract, for CREATE transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<Address>,
+    /// Signed envelope, as written by `sign --envelope`.
+    pub envelope: Envelope,
+}
+
+impl SignOutput {
+    /// Encode a signed transaction into an envelope from `sender` and describe it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`envelope::Error::Serialize`] if the envelope checksum cannot be computed.
+    pub fn new(
+        signed: AnyTxSigned,
+        sender: Address,
+        contract_address: Option<Address>,
+        metadata: envelope::Metadata,
+    ) -> Result<Self, envelope::Error> {
+        let hash = signed.hash();
+        let payload = envelope::Payload::Signed(signed.encode_2718());
+        let envelope = Envelope::new(payload, Some(sender), metadata)?;
+        Ok(Self { hash, sender, contract_address, envelope })
+    }
+}
+
+/// `submit --output json`: the transaction accepted by the node.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitOutput {
+    /// Transaction hash returned by the node.
+    pub hash: TxHash,
+    /// Account that signed the transaction.
+    pub sender: Address,
+    /// Nonce of the transaction.
+    pub nonce: u64,
+}
+
+/// Final state of a confirmation, see [`Confirmation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfirmationState {
+    /// Confirmed in the first block that included it.
+    Confirmed,
+    /// Confirmed in another block after a reorg orphaned the first one.
+    Reincluded,
+    /// Removed from the chain by a reorg and unknown to the node.
+    Dropped,
+    /// Never mined, because another transaction used its nonce.
+    Replaced,
+}
+
+/// `confirm --output json`: the confirmation state and the serialized receipt.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmOutput {
+    /// Hash of the monitored transaction.
+    pub hash: TxHash,
+    /// Whether the transaction succeeded, reverted or was dropped.
+    pub outcome: confirm::Outcome,
+    /// How the confirmation ended.
+    pub state: ConfirmationState,
+    /// Block that included the transaction before a reorg, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orphaned_block: Option<B256>,
+    /// JSON-RPC receipt of the confirmed transaction; absent when dropped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<TransactionReceipt>,
+    /// Why a reverted transaction failed, when requested and reproduced by the replay.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<revert::RevertReason>,
+    /// Receipt logs matching a known event, in receipt order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<logs::DecodedLog>,
+}
+
+impl ConfirmOutput {
+    /// Describe the confirmation of `hash`, decoding the receipt logs with `decoder`.
+    pub fn new(hash: TxHash, confirmation: Confirmation, decoder: &logs::LogDecoder) -> Self {
+        let outcome = confirm::Outcome::of(&confirmation);
+        let (state, orphaned_block, receipt) = match confirmation {
+            Confirmation::Confirmed(receipt) => (ConfirmationState::Confirmed, None, Some(receipt)),
+            Confirmation::Reincluded { receipt, orphaned_block } => {
+                (ConfirmationState::Reincluded, Some(orphaned_block), Some(receipt))
+            }
+            Confirmation::Dropped { orphaned_block } => {
+                (ConfirmationState::Dropped, Some(orphaned_block), None)
+            }
+            Confirmation::Replaced { .. } => (ConfirmationState::Replaced, None, None),
+        };
+        let events = receipt
+            .iter()
+            .flat_map(|receipt| receipt.inner.logs())
+            .filter_map(|log| decoder.decode(log))
+            .collect();
+        Self { hash, outcome, state, orphaned_block, receipt, revert_reason: None, events }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommonPayload, Eip4844Payload, LegacyPayload, UniquePayload, UnsignedTx};
+    use alloy::consensus::TxType;
+    use alloy::eips::eip2930::AccessList;
+    use alloy::primitives::keccak256;
+    use alloy::signers::local::PrivateKeySigner;
+    use envelope::Metadata;
+
+    #[tokio::test]
+    async fn sign_output_matches_the_signed_transaction() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let blob = UniquePayload::Eip4844(Eip4844Payload {
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            max_fee_per_blob_gas: 1_000_000_000,
+            access_list: AccessList::default(),
+            sidecar: sign::blob_sidecar([b"blob"])?,
+        });
+        let legacy = UniquePayload::Legacy(LegacyPayload { gas_price: 1_000_000_000 });
+        for unique in [legacy, blob] {
+            let tx = UnsignedTx {
+                common: CommonPayload {
+                    chain_id: 1,
+                    nonce: 3,
+                    gas_limit: 21_000,
+                    to: Some(Address::repeat_byte(0x02)),
+                    value: Wei::from(1),
+                    input: Bytes::new(),
+                },
+                unique,
+            };
+            let signed = tx.sign_async(&signer).await?;
+            let inspection = signed.inspect()?;
+            let output = SignOutput::new(signed, signer.address(), None, Metadata::default())?;
+            assert_eq!(output.hash, inspection.hash);
+            // The pooled EIP-4844 encoding carries the sidecar, which the hash excludes.
+            let pooled = output.envelope.clone().into_signed()?.encode_2718();
+            let is_blob = inspection.tx_type == TxType::Eip4844;
+            assert_eq!(keccak256(&pooled) == inspection.hash, !is_blob);
+            let json = serde_json::to_value(&output)?;
+            assert_eq!(json["hash"], serde_json::json!(inspection.hash));
+            assert_eq!(json["envelope"]["version"], 1);
+            assert_eq!(json["envelope"]["sender"], serde_json::json!(signer.address()));
+            assert!(json.get("contractAddress").is_none());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn confirm_output_serializes_receipt_as_json_rpc() -> eyre::Result<()> {
+        let hash = TxHash::repeat_byte(0x11);
+        let block = B256::repeat_byte(0xaa);
+        let receipt = serde_json::from_value(confirm::tests::receipt(hash, 100, block))?;
+        let decoder = logs::LogDecoder::new(&[]);
+        let output = ConfirmOutput::new(hash, Confirmation::Confirmed(receipt), &decoder);
+        let json = serde_json::to_value(&output)?;
+        assert_eq!((&json["outcome"], &json["state"]), (&"success".into(), &"confirmed".into()));
+        assert_eq!(json["receipt"]["status"], "0x1");
+        assert_eq!(json["receipt"]["blockNumber"], "0x64");
+        assert!(json.get("orphanedBlock").is_none());
+        assert!(json.get("events").is_none());
+
+        let dropped =
+            ConfirmOutput::new(hash, Confirmation::Dropped { orphaned_block: block }, &decoder);
+        let json = serde_json::to_value(&dropped)?;
+        assert_eq!(json["state"], "dropped");
+        assert!(json.get("receipt").is_none());
+        Ok(())
+    }
+}
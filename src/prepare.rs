@@ -0,0 +1,108 @@
+use super::*;
+use alloy::network::{Ethereum, TransactionBuilder};
+use alloy::primitives::{Address, Bytes};
+use alloy::providers::{Provider, RootProvider};
+use alloy::rpc::types::TransactionRequest;
+use alloy::transports::TransportError;
+use sign::{UniquePayload, UnsignedTx};
+
+/// Errors for online transaction preparation.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// RPC call failed while preparing the transaction: {0}
+    Rpc(eyre::Report),
+}
+
+/// Fee model of the transaction to prepare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeModel {
+    /// EIP-1559 fees suggested from recent blocks via `eth_feeHistory`.
+    Eip1559,
+    /// Legacy gas price suggested via `eth_gasPrice`.
+    Legacy,
+}
+
+/// Fetch everything `sign` needs for a transaction from `sender` to `to` (or a deployment).
+///
+/// Queries the chain ID, the pending nonce of `sender`, fee suggestions for `fee_model`
+/// and a gas estimate for the call. Wrap the result in an [`Envelope`] to carry it to
+/// the offline machine.
+///
+/// # Errors
+///
+/// Returns [`Error::Rpc`] if any RPC call fails, including when gas estimation reverts.
+pub async fn prepare(
+    rpc_url: url::Url,
+    sender: Address,
+    to: Option<Address>,
+    value: Wei,
+    input: Bytes,
+    fee_model: FeeModel,
+) -> Result<UnsignedTx, Error> {
+    let rpc = |e: TransportError| Error::Rpc(eyre::eyre!(e));
+    let provider = RootProvider::<Ethereum>::new_http(rpc_url);
+
+    let chain_id = provider.get_chain_id().await.map_err(rpc)?;
+    let nonce = provider.get_transaction_count(sender).pending().await.map_err(rpc)?;
+    let request =
+        TransactionRequest::default().with_from(sender).with_value(value).with_input(input.clone());
+    let request = match to {
+        Some(to) => request.with_to(to),
+        None => request.into_create(),
+    };
+    let gas_limit = provider.estimate_gas(request).await.map_err(rpc)?;
+    let unique = match fee_model {
+        FeeModel::Eip1559 => {
+            let fees = provider.estimate_eip1559_fees().await.map_err(rpc)?;
+            UniquePayload::Eip1559(Eip1559Payload {
+                max_fee_per_gas: fees.max_fee_per_gas,
+                max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+                access_list: Default::default(),
+            })
+        }
+        FeeModel::Legacy => {
+            let gas_price = provider.get_gas_price().await.map_err(rpc)?;
+            UniquePayload::Legacy(LegacyPayload { gas_price })
+        }
+    };
+
+    let common = CommonPayload { chain_id, nonce, gas_limit, to, value, input };
+    Ok(UnsignedTx { common, unique })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::node_bindings::Anvil;
+    use alloy::primitives::U256;
+    use alloy::signers::local::PrivateKeySigner;
+
+    #[tokio::test]
+    async fn smoke_test_prepare() -> eyre::Result<()> {
+        // Ensure `anvil` is available in $PATH.
+        let anvil = Anvil::new().block_time(1).try_spawn()?;
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let rpc_url = anvil.endpoint_url();
+
+        let bob = anvil.addresses()[1];
+        let value = U256::from(1_000_000_000_000_000_u64);
+        let prepared = prepare(
+            rpc_url.clone(),
+            signer.address(),
+            Some(bob),
+            value,
+            Bytes::new(),
+            FeeModel::Eip1559,
+        )
+        .await?;
+        assert_eq!(prepared.common.chain_id, anvil.chain_id());
+        assert_eq!(prepared.common.nonce, 0);
+        assert_eq!(prepared.common.gas_limit, 21_000);
+
+        let signed = prepared.sign_async(&signer).await?;
+        let tx_hash = signed.submit(rpc_url.clone()).await?;
+        let receipt = get_receipt(tx_hash, rpc_url).await?;
+        assert!(receipt.status());
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::Path;
+
+use super::*;
+use qrcode::{Color, QrCode, render::unicode::Dense1x2};
+
+/// Default maximum payload bytes per UR frame, small enough for a quickly scanned QR code.
+pub const DEFAULT_FRAGMENT_LEN: usize = 200;
+
+/// Light modules around the code, as required by the QR specification.
+const QUIET_ZONE: usize = 4;
+
+/// Errors for QR-code rendering and UR frame reassembly.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to encode the payload: {0}
+    Encode(eyre::Report),
+    /// Failed to decode a UR frame: {0}
+    Decode(eyre::Report),
+    /// Not enough frames to reassemble the payload; keep scanning
+    Incomplete,
+    /// Failed to write the PNG image: {0}
+    Png(eyre::Report),
+}
+
+/// Encode `data` as Uniform Resource (BC-UR) frames of type `bytes`.
+///
+/// Payloads of at most `max_fragment_len` bytes yield a single `ur:bytes/...` frame;
+/// larger ones are split by the UR fountain encoder into `ur:bytes/<n>-<total>/...`
+/// frames, all of which are needed for reassembly when no extra parts are scanned.
+///
+/// # Errors
+///
+/// Returns [`Error::Encode`] if `data` is empty or `max_fragment_len` is zero.
+pub fn encode_ur(data: &[u8], max_fragment_len: usize) -> Result<Vec<String>, Error> {
+    if data.is_empty() || max_fragment_len == 0 {
+        return Err(Error::Encode(eyre::eyre!("empty payload or zero fragment length")));
+    }
+    if data.len() <= max_fragment_len {
+        return Ok(vec![ur::encode(data, &ur::Type::Bytes)]);
+    }
+    let mut encoder = ur::Encoder::bytes(data, max_fragment_len)
+        .map_err(|e| Error::Encode(eyre::eyre!(e.to_string())))?;
+    (0..encoder.fragment_count())
+        .map(|_| encoder.next_part().map_err(|e| Error::Encode(eyre::eyre!(e.to_string()))))
+        .collect()
+}
+
+/// Reassemble the payload from scanned UR frames, in any order and with repetitions.
+///
+/// Frames after the payload is complete are ignored.
+///
+/// # Errors
+///
+/// Returns [`Error::Decode`] for malformed or inconsistent frames and
+/// [`Error::Incomplete`] if the frames do not cover the whole payload.
+pub fn decode_ur<'a>(frames: impl IntoIterator<Item = &'a str>) -> Result<Vec<u8>, Error> {
+    let decode = |e: ur::ur::Error| Error::Decode(eyre::eyre!(e.to_string()));
+    let mut decoder = ur::Decoder::default();
+    for frame in frames.into_iter().map(str::trim).filter(|frame| !frame.is_empty()) {
+        if let (ur::ur::Kind::SinglePart, data) = ur::decode(frame).map_err(decode)? {
+            return Ok(data);
+        }
+        decoder.receive(frame).map_err(decode)?;
+        if decoder.complete() {
+            break;
+        }
+    }
+    decoder.message().map_err(decode)?.ok_or(Error::Incomplete)
+}
+
+/// Build the QR code of a UR frame, upper-cased so it fits the compact alphanumeric mode.
+fn qr_code(frame: &str) -> Result<QrCode, Error> {
+    QrCode::new(frame.to_ascii_uppercase()).map_err(|e| Error::Encode(eyre::eyre!(e)))
+}
+
+/// Render a UR frame as a QR code of Unicode half blocks for a dark-background terminal.
+///
+/// # Errors
+///
+/// Returns [`Error::Encode`] if the frame is too long for a single QR code.
+pub fn render_terminal(frame: &str) -> Result<String, Error> {
+    Ok(qr_code(frame)?
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// Write a UR frame as a black-on-white QR code PNG, `scale` pixels per module.
+///
+/// # Errors
+///
+/// Returns [`Error::Encode`] if the frame is too long for a single QR code and
+/// [`Error::Png`] if the image cannot be written.
+pub fn write_png(frame: &str, scale: usize, path: impl AsRef<Path>) -> Result<(), Error> {
+    let png = |e: &dyn std::fmt::Display| Error::Png(eyre::eyre!(e.to_string()));
+    let code = qr_code(frame)?;
+    let (width, colors) = (code.width(), code.to_colors());
+    let side = (width + 2 * QUIET_ZONE) * scale;
+    let mut pixels = vec![u8::MAX; side * side];
+    for (index, _) in colors.iter().enumerate().filter(|(_, color)| **color == Color::Dark) {
+        let (x, y) = ((index % width + QUIET_ZONE) * scale, (index / width + QUIET_ZONE) * scale);
+        for row in y..y + scale {
+            pixels[row * side + x..row * side + x + scale].fill(0);
+        }
+    }
+
+    let side = u32::try_from(side).map_err(|e| png(&e))?;
+    let file = std::fs::File::create(path).map_err(|e| png(&e))?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), side, side);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|e| png(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_payload_is_a_single_frame() -> eyre::Result<()> {
+        let frames = encode_ur(b"data", DEFAULT_FRAGMENT_LEN)?;
+        assert_eq!(frames, ["ur:bytes/iehsjyhspmwfwfia"]);
+        assert_eq!(decode_ur(frames.iter().map(String::as_str))?, b"data");
+        assert!(render_terminal(&frames[0])?.lines().count() > 10);
+        Ok(())
+    }
+
+    #[test]
+    fn reassembles_multi_part_frames_in_any_order() -> eyre::Result<()> {
+        let data: Vec<u8> = (0..=255).cycle().take(1_000).collect();
+        let frames = encode_ur(&data, 100)?;
+        assert_eq!(frames.len(), 10);
+        assert!(frames[0].starts_with("ur:bytes/1-10/"));
+
+        // Scanners see frames out of order, repeated and upper-cased.
+        let scanned: Vec<String> =
+            frames.iter().rev().chain(&frames[..3]).map(|f| f.to_ascii_uppercase()).collect();
+        assert_eq!(decode_ur(scanned.iter().map(String::as_str))?, data);
+        assert!(matches!(
+            decode_ur(frames[1..].iter().map(String::as_str)),
+            Err(Error::Incomplete)
+        ));
+        assert!(matches!(decode_ur(["ur:bytes/1-10/zzzz"]), Err(Error::Decode(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn writes_png_with_quiet_zone() -> eyre::Result<()> {
+        let path = std::env::temp_dir().join(format!("qr-{}.png", std::process::id()));
+        write_png("ur:bytes/iehsjyhspmwfwfia", 4, &path)?;
+        let decoder = png::Decoder::new(std::fs::File::open(&path)?);
+        let info = decoder.read_info()?.info().clone();
+        let modules = qr_code("ur:bytes/iehsjyhspmwfwfia")?.width() + 2 * QUIET_ZONE;
+        assert_eq!(info.width as usize, modules * 4);
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+}
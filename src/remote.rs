@@ -0,0 +1,266 @@
+use super::*;
+use alloy::consensus::{SignableTransaction, TxEnvelope};
+use alloy::eips::{Decodable2718, eip7702::SignedAuthorization};
+use alloy::primitives::{Address, B256, Bytes};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::{TransactionInput, TransactionRequest};
+use alloy::signers::Signature;
+use alloy::transports::http::reqwest;
+use sign::SignerBackend;
+
+/// Errors for signing through a remote signing service.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Request to the remote signer failed: {0}
+    Request(eyre::Report),
+    /// The remote signer returned an unexpected response: {0}
+    Response(eyre::Report),
+    /// The remote signature recovers to {0} instead of {1}; the signer altered the transaction or used another key
+    WrongSigner(Address, Address),
+}
+
+/// Wire protocol spoken by a remote signing service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteApi {
+    /// `eth_signTransaction` JSON-RPC, as served by Clef and Web3Signer's Eth1 proxy.
+    JsonRpc,
+    /// Web3Signer REST: `POST /api/v1/eth1/sign/{address}` with the transaction's signing payload.
+    Web3Signer,
+}
+
+/// A key held by an external HTTP signing service, usable with [`Tx::sign_async`](sign::Tx::sign_async).
+///
+/// Every returned signature is checked to recover to `address` over the exact transaction
+/// that was requested, so a signer that fills in or alters fields is rejected.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    url: url::Url,
+    address: Address,
+    api: RemoteApi,
+}
+
+impl RemoteSigner {
+    /// Sign as `address` through the service at `url` (the base URL for [`RemoteApi::Web3Signer`]).
+    pub fn new(url: url::Url, address: Address, api: RemoteApi) -> Self {
+        Self { url, address, api }
+    }
+
+    /// Ask `eth_signTransaction` for the raw signed transaction and take its signature.
+    async fn sign_json_rpc(
+        &self,
+        tx: &dyn SignableTransaction<Signature>,
+    ) -> Result<Signature, Error> {
+        let client = RpcClient::new_http(self.url.clone());
+        let response: serde_json::Value = client
+            .request("eth_signTransaction", (transaction_request(tx, self.address),))
+            .await
+            .map_err(|e| Error::Request(eyre::eyre!(e)))?;
+        // Web3Signer answers with the raw transaction, Clef with `{"raw": ..., "tx": ...}`.
+        let raw = response
+            .as_str()
+            .or_else(|| response.get("raw").and_then(serde_json::Value::as_str))
+            .ok_or_else(|| Error::Response(eyre::eyre!("no raw transaction in {response}")))?;
+        let raw = raw.parse::<Bytes>().map_err(|e| Error::Response(eyre::eyre!(e)))?;
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_ref())
+            .map_err(|e| Error::Response(eyre::eyre!(e)))?;
+        Ok(*envelope.signature())
+    }
+
+    /// Ask Web3Signer to sign the keccak-256 hash of the transaction's signing payload.
+    async fn sign_web3signer(
+        &self,
+        tx: &dyn SignableTransaction<Signature>,
+    ) -> Result<Signature, Error> {
+        let mut payload = Vec::new();
+        tx.encode_for_signing(&mut payload);
+        let endpoint = format!(
+            "{}/api/v1/eth1/sign/{}",
+            self.url.as_str().trim_end_matches('/'),
+            self.address
+        );
+        let response = reqwest::Client::new()
+            .post(endpoint)
+            .json(&serde_json::json!({ "data": Bytes::from(payload) }))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| Error::Request(eyre::eyre!(e)))?
+            .text()
+            .await
+            .map_err(|e| Error::Response(eyre::eyre!(e)))?;
+        response.trim().parse::<Signature>().map_err(|e| Error::Response(eyre::eyre!(e)))
+    }
+
+    /// Request a signature for `tx` and check that it was made by this signer's key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Request`] if the service is unreachable or rejects the request,
+    /// [`Error::Response`] if its answer cannot be parsed, and [`Error::WrongSigner`] if the
+    /// signature does not recover to this signer's address over `tx`.
+    pub async fn sign(&self, tx: &dyn SignableTransaction<Signature>) -> Result<Signature, Error> {
+        let signature = match self.api {
+            RemoteApi::JsonRpc => self.sign_json_rpc(tx).await?,
+            RemoteApi::Web3Signer => self.sign_web3signer(tx).await?,
+        };
+        let recovered = signature
+            .recover_address_from_prehash(&tx.signature_hash())
+            .map_err(|e| Error::Response(eyre::eyre!(e)))?;
+        if recovered != self.address {
+            return Err(Error::WrongSigner(recovered, self.address));
+        }
+        Ok(signature)
+    }
+}
+
+impl SignerBackend for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> Result<Signature, sign::Error> {
+        self.sign(tx).await.map_err(|e| sign::Error::Sign(eyre::eyre!(e)))
+    }
+}
+
+/// Describe `tx` as the `eth_signTransaction` parameter object, sending from `from`.
+fn transaction_request(
+    tx: &dyn SignableTransaction<Signature>,
+    from: Address,
+) -> TransactionRequest {
+    TransactionRequest {
+        from: Some(from),
+        to: Some(tx.kind()),
+        gas_price: tx.gas_price(),
+        max_fee_per_gas: tx.is_dynamic_fee().then(|| tx.max_fee_per_gas()),
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas(),
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas(),
+        gas: Some(tx.gas_limit()),
+        value: Some(tx.value()),
+        input: TransactionInput::both(tx.input().clone()),
+        nonce: Some(tx.nonce()),
+        chain_id: tx.chain_id(),
+        access_list: tx.access_list().cloned(),
+        transaction_type: Some(tx.ty()),
+        blob_versioned_hashes: tx.blob_versioned_hashes().map(<[B256]>::to_vec),
+        sidecar: None,
+        authorization_list: tx.authorization_list().map(<[SignedAuthorization]>::to_vec),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::{CommonPayload, Eip1559Payload, sign::Build};
+    use alloy::consensus::TxEip1559;
+    use alloy::eips::{Encodable2718, eip2930::AccessList};
+    use alloy::network::TxSignerSync;
+    use alloy::primitives::{U256, keccak256};
+    use alloy::signers::{SignerSync, local::PrivateKeySigner};
+
+    /// Serve both remote signer APIs over plain HTTP with `key`, one request per connection.
+    fn mock_signer(key: PrivateKeySigner) -> eyre::Result<url::Url> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?).parse()?;
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(&stream);
+                let (mut request_line, mut content_length) = (String::new(), 0);
+                reader.read_line(&mut request_line).ok();
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).ok();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap_or_default();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).ok();
+                let body: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+
+                let response = if request_line.contains("/api/v1/eth1/sign/") {
+                    let data: Bytes = serde_json::from_value(body["data"].clone()).unwrap();
+                    let signature = key.sign_hash_sync(&keccak256(&data)).unwrap();
+                    format!("0x{}", hex::encode(signature.as_bytes()))
+                } else {
+                    let request: TransactionRequest =
+                        serde_json::from_value(body["params"][0].clone()).unwrap();
+                    let mut tx = request.build_typed_tx().unwrap();
+                    let signature = key.sign_transaction_sync(&mut tx).unwrap();
+                    let raw = TxEnvelope::from(tx.into_signed(signature)).encoded_2718();
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": body["id"],
+                        "result": Bytes::from(raw),
+                    })
+                    .to_string()
+                };
+                write!(
+                    &stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nContent-Type: application/json\r\n\
+                     Connection: close\r\n\r\n{response}",
+                    response.len()
+                )
+                .ok();
+            }
+        });
+        Ok(url)
+    }
+
+    fn common_payload() -> CommonPayload {
+        CommonPayload {
+            chain_id: 11155111,
+            nonce: 3,
+            gas_limit: 21_000,
+            to: Some(Address::repeat_byte(0x11)),
+            value: U256::from(1_000_000_000_000_000_u64),
+            input: Bytes::new(),
+        }
+    }
+
+    fn payload() -> Eip1559Payload {
+        Eip1559Payload {
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_500_000_000,
+            access_list: AccessList::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn remote_signatures_match_local_signing() -> eyre::Result<()> {
+        let key = PrivateKeySigner::random();
+        let url = mock_signer(key.clone())?;
+        let local: TxEip2718Bytes<TxEip1559> =
+            common_payload().build(payload())?.sign(&key)?.encode_2718();
+
+        for api in [RemoteApi::JsonRpc, RemoteApi::Web3Signer] {
+            let remote = RemoteSigner::new(url.clone(), key.address(), api);
+            let signed: TxEip2718Bytes<TxEip1559> =
+                common_payload().build(payload())?.sign_async(&remote).await?.encode_2718();
+            assert_eq!(signed.as_ref(), local.as_ref());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejects_signature_from_another_key() -> eyre::Result<()> {
+        let url = mock_signer(PrivateKeySigner::random())?;
+        let expected = Address::repeat_byte(0x22);
+        let remote = RemoteSigner::new(url, expected, RemoteApi::Web3Signer);
+        let tx = TxEip1559 { chain_id: 1, gas_limit: 21_000, ..Default::default() };
+        let result = remote.sign(&tx).await;
+        assert!(matches!(result, Err(Error::WrongSigner(_, address)) if address == expected));
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+use super::*;
+use alloy::primitives::{Address, Bytes};
+use inspect::Inspection;
+use sign::UnsignedTx;
+
+/// Smallest fee increase, in percent, that nodes accept for a same-nonce replacement
+/// (geth's `txpool.pricebump`).
+pub const MIN_BUMP_PERCENT: u32 = 10;
+
+/// Smallest fee increase, in percent, for replacing an EIP-4844 blob transaction
+/// (geth's `blobpool.pricebump`).
+pub const MIN_BLOB_BUMP_PERCENT: u32 = 100;
+
+/// Errors for building replacement and cancellation transactions.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to read the original transaction: {0}
+    Original(eyre::Report),
+    /// The original transaction has no chain ID; sign its replacement with explicit options
+    MissingChainId,
+    /// A {1}% bump is too small; nodes require at least {0}% to replace this transaction
+    BumpTooSmall(u32, u32),
+    /// The new {0} of {1} wei is below {2} wei, the minimum nodes accept as a replacement
+    FeeTooLow(&'static str, u128, u128),
+    /// Blob transactions can only be replaced by blob transactions; use a replacement instead
+    BlobCancel,
+}
+
+/// Fees to use instead of the bumped ones; each must still meet the minimum bump.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeOverrides {
+    /// Gas price, for legacy and EIP-2930 transactions.
+    pub gas_price: Option<u128>,
+    /// Maximum total fee per gas, for dynamic-fee transactions.
+    pub max_fee_per_gas: Option<u128>,
+    /// Maximum priority fee per gas, for dynamic-fee transactions.
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Maximum fee per blob gas, for EIP-4844 transactions.
+    pub max_fee_per_blob_gas: Option<u128>,
+}
+
+/// Increase `fee` by `percent`, rounding up so the result never falls short of the bump.
+pub fn bump(fee: u128, percent: u32) -> u128 {
+    let percent = u128::from(percent);
+    let increase = (fee / 100).saturating_mul(percent) + (fee % 100 * percent).div_ceil(100);
+    fee.saturating_add(increase)
+}
+
+/// Apply the bump to one fee, or check its override against the minimum bump.
+fn bump_fee(
+    name: &'static str,
+    original: Option<u128>,
+    percent: u32,
+    min_percent: u32,
+    fee_override: Option<u128>,
+) -> Result<u128, Error> {
+    let original = original.unwrap_or_default();
+    let minimum = bump(original, min_percent);
+    match fee_override {
+        Some(fee) if fee < minimum => Err(Error::FeeTooLow(name, fee, minimum)),
+        Some(fee) => Ok(fee),
+        None => Ok(bump(original, percent)),
+    }
+}
+
+/// Same-nonce transaction with the fields of `original` and fees raised by `bump_percent`.
+fn replacement(
+    original: &AnyTxSigned,
+    inspection: Inspection,
+    bump_percent: u32,
+    overrides: &FeeOverrides,
+) -> Result<UnsignedTx, Error> {
+    let min_percent = match original {
+        AnyTxSigned::Eip4844(_) => MIN_BLOB_BUMP_PERCENT,
+        _ => MIN_BUMP_PERCENT,
+    };
+    if bump_percent < min_percent {
+        return Err(Error::BumpTooSmall(min_percent, bump_percent));
+    }
+    let bump = |name, original, fee_override| {
+        bump_fee(name, original, bump_percent, min_percent, fee_override)
+    };
+    let max_fee_per_gas =
+        || bump("max fee per gas", inspection.max_fee_per_gas, overrides.max_fee_per_gas);
+    let max_priority_fee_per_gas = || {
+        bump(
+            "max priority fee per gas",
+            inspection.max_priority_fee_per_gas,
+            overrides.max_priority_fee_per_gas,
+        )
+    };
+    let gas_price = || bump("gas price", inspection.gas_price, overrides.gas_price);
+    let access_list = inspection.access_list.unwrap_or_default();
+
+    let unique = match original {
+        AnyTxSigned::Legacy(_) => UniquePayload::Legacy(LegacyPayload { gas_price: gas_price()? }),
+        AnyTxSigned::Eip2930(_) => {
+            UniquePayload::Eip2930(Eip2930Payload { gas_price: gas_price()?, access_list })
+        }
+        AnyTxSigned::Eip1559(_) => UniquePayload::Eip1559(Eip1559Payload {
+            max_fee_per_gas: max_fee_per_gas()?,
+            max_priority_fee_per_gas: max_priority_fee_per_gas()?,
+            access_list,
+        }),
+        AnyTxSigned::Eip4844(TxSigned(signed)) => UniquePayload::Eip4844(Eip4844Payload {
+            max_fee_per_gas: max_fee_per_gas()?,
+            max_priority_fee_per_gas: max_priority_fee_per_gas()?,
+            max_fee_per_blob_gas: bump(
+                "max fee per blob gas",
+                inspection.max_fee_per_blob_gas,
+                overrides.max_fee_per_blob_gas,
+            )?,
+            access_list,
+            sidecar: signed.tx().sidecar.clone(),
+        }),
+        AnyTxSigned::Eip7702(_) => UniquePayload::Eip7702(Eip7702Payload {
+            max_fee_per_gas: max_fee_per_gas()?,
+            max_priority_fee_per_gas: max_priority_fee_per_gas()?,
+            access_list,
+            authorization_list: inspection.authorization_list.unwrap_or_default(),
+        }),
+    };
+    let common = CommonPayload {
+        chain_id: inspection.chain_id.ok_or(Error::MissingChainId)?,
+        nonce: inspection.nonce,
+        gas_limit: inspection.gas_limit,
+        to: inspection.to.to().copied(),
+        value: inspection.value,
+        input: inspection.input,
+    };
+    Ok(UnsignedTx { common, unique })
+}
+
+/// Build a speed-up of `original`: the same transaction with fees raised by `bump_percent`.
+///
+/// Returns the transaction together with the original sender, who has to sign it.
+///
+/// # Errors
+///
+/// Returns [`Error::Original`] if the original sender cannot be recovered,
+/// [`Error::BumpTooSmall`] if `bump_percent` is below [`MIN_BUMP_PERCENT`] (or
+/// [`MIN_BLOB_BUMP_PERCENT`] for blob transactions), [`Error::FeeTooLow`] if an override
+/// does not meet that minimum, and [`Error::MissingChainId`] for pre-EIP-155 originals.
+pub fn replace(
+    original: &AnyTxSigned,
+    bump_percent: u32,
+    overrides: &FeeOverrides,
+) -> Result<(UnsignedTx, Address), Error> {
+    let inspection = original.inspect().map_err(|e| Error::Original(eyre::eyre!(e)))?;
+    let sender = inspection.sender;
+    Ok((replacement(original, inspection, bump_percent, overrides)?, sender))
+}
+
+/// Build a cancellation of `original`: a zero-value transfer from its sender to itself
+/// with the same nonce and fees raised by `bump_percent`.
+///
+/// EIP-7702 originals are cancelled with an EIP-1559 transaction, since a set-code
+/// transaction needs a delegation.
+///
+/// # Errors
+///
+/// Returns the errors of [`replace`], and [`Error::BlobCancel`] for EIP-4844 originals,
+/// which the blob pool only lets other blob transactions replace.
+pub fn cancel(
+    original: &AnyTxSigned,
+    bump_percent: u32,
+    overrides: &FeeOverrides,
+) -> Result<(UnsignedTx, Address), Error> {
+    let (replacement, sender) = replace(original, bump_percent, overrides)?;
+    let unique = match replacement.unique {
+        UniquePayload::Legacy(unique) => UniquePayload::Legacy(unique),
+        UniquePayload::Eip2930(unique) => {
+            UniquePayload::Eip2930(Eip2930Payload { access_list: Default::default(), ..unique })
+        }
+        UniquePayload::Eip1559(unique) => {
+            UniquePayload::Eip1559(Eip1559Payload { access_list: Default::default(), ..unique })
+        }
+        UniquePayload::Eip7702(unique) => UniquePayload::Eip1559(Eip1559Payload {
+            max_fee_per_gas: unique.max_fee_per_gas,
+            max_priority_fee_per_gas: unique.max_priority_fee_per_gas,
+            access_list: Default::default(),
+        }),
+        UniquePayload::Eip4844(_) => return Err(Error::BlobCancel),
+    };
+    let common = CommonPayload {
+        gas_limit: 21_000,
+        to: Some(sender),
+        value: Wei::ZERO,
+        input: Bytes::new(),
+        ..replacement.common
+    };
+    Ok((UnsignedTx { common, unique }, sender))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::eips::eip2930::AccessList;
+    use alloy::primitives::{U256, bytes};
+    use alloy::signers::local::PrivateKeySigner;
+
+    async fn original(
+        signer: &PrivateKeySigner,
+        unique: UniquePayload,
+    ) -> eyre::Result<AnyTxSigned> {
+        let common = CommonPayload {
+            chain_id: 11155111,
+            nonce: 42,
+            gas_limit: 60_000,
+            to: Some(Address::repeat_byte(0x11)),
+            value: U256::from(1_000),
+            input: bytes!("a9059cbb"),
+        };
+        Ok(UnsignedTx { common, unique }.sign_async(signer).await?)
+    }
+
+    fn eip1559() -> UniquePayload {
+        UniquePayload::Eip1559(Eip1559Payload {
+            max_fee_per_gas: 20_000_000_001,
+            max_priority_fee_per_gas: 1_000_000_000,
+            access_list: AccessList::default(),
+        })
+    }
+
+    #[test]
+    fn bump_rounds_up() {
+        assert_eq!(bump(100, 10), 110);
+        assert_eq!(bump(20_000_000_001, 10), 22_000_000_002);
+        assert_eq!(bump(u128::MAX, 10), u128::MAX);
+    }
+
+    #[tokio::test]
+    async fn replacement_keeps_fields_and_bumps_fees() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let original = original(&signer, eip1559()).await?;
+
+        let (tx, sender) = replace(&original, 12, &FeeOverrides::default())?;
+        assert_eq!(sender, signer.address());
+        assert_eq!((tx.common.nonce, tx.common.gas_limit), (42, 60_000));
+        assert_eq!(tx.common.input, bytes!("a9059cbb"));
+        let UniquePayload::Eip1559(unique) = tx.unique else { panic!("type changed") };
+        assert_eq!(unique.max_fee_per_gas, 22_400_000_002);
+        assert_eq!(unique.max_priority_fee_per_gas, 1_120_000_000);
+
+        assert!(matches!(
+            replace(&original, 5, &FeeOverrides::default()),
+            Err(Error::BumpTooSmall(10, 5))
+        ));
+        let overrides =
+            FeeOverrides { max_priority_fee_per_gas: Some(1_050_000_000), ..Default::default() };
+        assert!(matches!(
+            replace(&original, 10, &overrides),
+            Err(Error::FeeTooLow("max priority fee per gas", 1_050_000_000, 1_100_000_000))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancellation_is_zero_value_self_transfer() -> eyre::Result<()> {
+        let signer = PrivateKeySigner::random();
+        let legacy = UniquePayload::Legacy(LegacyPayload { gas_price: 10_000_000_000 });
+        let original = original(&signer, legacy).await?;
+
+        let (tx, _) = cancel(&original, MIN_BUMP_PERCENT, &FeeOverrides::default())?;
+        let inspection = tx.sign_async(&signer).await?.inspect()?;
+        assert_eq!(inspection.to, signer.address().into());
+        assert_eq!((inspection.nonce, inspection.gas_limit), (42, 21_000));
+        assert_eq!(inspection.value, U256::ZERO);
+        assert!(inspection.input.is_empty());
+        assert_eq!(inspection.gas_price, Some(11_000_000_000));
+        Ok(())
+    }
+}
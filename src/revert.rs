@@ -0,0 +1,198 @@
+use std::fmt;
+
+use super::*;
+use alloy::dyn_abi::ErrorExt;
+use alloy::eips::BlockId;
+use alloy::json_abi::JsonAbi;
+use alloy::network::Ethereum;
+use alloy::primitives::{TxHash, U256};
+use alloy::providers::{Provider, RootProvider};
+use alloy::sol_types::{Panic, Revert, SolError};
+
+/// Errors for replaying a failed transaction.
+#[derive(Display, Error, Debug)]
+pub enum Error {
+    /// Failed to fetch the transaction from the RPC endpoint: {0}
+    Transaction(eyre::Report),
+    /// Transaction {0} is unknown to the node
+    NotFound(TxHash),
+    /// Transaction {0} is not mined yet, so there is no block to replay it in
+    Pending(TxHash),
+    /// The replay `eth_call` failed: {0}
+    Call(eyre::Report),
+}
+
+/// Why a transaction reverted, decoded from its revert data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RevertReason {
+    /// `require` or `revert` with a message, encoded as `Error(string)`.
+    Error(String),
+    /// Failed `assert`, arithmetic check or similar, encoded as `Panic(uint256)`.
+    #[serde(rename_all = "camelCase")]
+    Panic {
+        /// Solidity panic code, e.g. `0x11` for an arithmetic overflow.
+        code: U256,
+        /// Meaning of the code, if it is a known one.
+        description: Option<String>,
+    },
+    /// Custom error declared in one of the supplied ABIs.
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        /// Error signature, e.g. `InsufficientBalance(uint256,uint256)`.
+        signature: String,
+        /// Decoded arguments in Solidity literal syntax.
+        args: Vec<String>,
+    },
+    /// Revert data matching no known error; empty for a bare `revert()`.
+    Unknown(Bytes),
+    /// Message of a node that failed the replay without revert data, e.g. out of gas.
+    Message(String),
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(message) => write!(f, "Error({message:?})"),
+            Self::Panic { code, description: Some(description) } => {
+                write!(f, "Panic({code:#x}): {description}")
+            }
+            Self::Panic { code, description: None } => write!(f, "Panic({code:#x})"),
+            Self::Custom { signature, args } => {
+                let name = signature.split('(').next().unwrap_or_default();
+                write!(f, "{name}({})", args.join(", "))
+            }
+            Self::Unknown(data) if data.is_empty() => f.write_str("reverted without data"),
+            Self::Unknown(data) => write!(f, "unknown revert data {data}"),
+            Self::Message(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Decode revert data as `Error(string)`, `Panic(uint256)` or a custom error from `abis`.
+pub fn decode(data: &[u8], abis: &[JsonAbi]) -> RevertReason {
+    if let Ok(revert) = Revert::abi_decode(data) {
+        return RevertReason::Error(revert.reason);
+    }
+    if let Ok(panic) = Panic::abi_decode(data) {
+        let description = panic.kind().map(|kind| kind.to_string());
+        return RevertReason::Panic { code: panic.code, description };
+    }
+    let custom = abis.iter().flat_map(|abi| abi.errors()).find_map(|error| {
+        let decoded = error.decode_error(data).ok()?;
+        let args = decoded.body.iter().map(abi::format_value).collect();
+        Some(RevertReason::Custom { signature: error.signature(), args })
+    });
+    custom.unwrap_or_else(|| RevertReason::Unknown(Bytes::copy_from_slice(data)))
+}
+
+/// Replay a mined transaction with `eth_call` on the state of its parent block and decode
+/// why it reverts.
+///
+/// Fees and nonce are left out of the call so that only the execution is replayed. The
+/// parent state does not include the transactions before it in the same block, so the
+/// replay can differ from the original execution; `None` means it did not revert.
+///
+/// # Errors
+///
+/// Returns [`Error::Transaction`], [`Error::NotFound`] or [`Error::Pending`] if the
+/// transaction cannot be fetched from a block, and [`Error::Call`] if the call fails
+/// without an execution error.
+pub async fn replay(
+    tx_hash: TxHash,
+    rpc_url: url::Url,
+    abis: &[JsonAbi],
+) -> Result<Option<RevertReason>, Error> {
+    let provider = RootProvider::<Ethereum>::new_http(rpc_url);
+    let tx = provider
+        .get_transaction_by_hash(tx_hash)
+        .await
+        .map_err(|e| Error::Transaction(eyre::eyre!(e)))?
+        .ok_or(Error::NotFound(tx_hash))?;
+    let block_number = tx.block_number.ok_or(Error::Pending(tx_hash))?;
+
+    let mut request = tx.into_request();
+    request.nonce = None;
+    request.gas_price = None;
+    request.max_fee_per_gas = None;
+    request.max_priority_fee_per_gas = None;
+    let parent = BlockId::number(block_number.saturating_sub(1));
+    let Err(error) = provider.call(request).block(parent).await else {
+        return Ok(None);
+    };
+    let Some(payload) = error.as_error_resp() else {
+        return Err(Error::Call(eyre::eyre!(error)));
+    };
+    Ok(Some(match payload.as_revert_data() {
+        Some(data) => decode(&data, abis),
+        None => RevertReason::Message(payload.message.to_string()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::confirm::tests::{mock_rpc, quantity};
+    use serde_json::{Value, json};
+
+    const ABI: &str = r#"[{"type": "error", "name": "InsufficientBalance",
+        "inputs": [{"name": "available", "type": "uint256"},
+                   {"name": "required", "type": "uint256"}]}]"#;
+
+    #[test]
+    fn decodes_builtin_and_custom_errors() -> eyre::Result<()> {
+        let error = Revert::from("insufficient allowance").abi_encode();
+        assert_eq!(decode(&error, &[]), RevertReason::Error("insufficient allowance".into()));
+
+        let panic = Panic::from(U256::from(0x11)).abi_encode();
+        let RevertReason::Panic { code, description } = decode(&panic, &[]) else {
+            panic!("expected a panic");
+        };
+        assert_eq!((code, description.is_some()), (U256::from(0x11), true));
+
+        let abi = abi::parse_json_abi(ABI)?;
+        let custom = abi::encode_call(
+            &abi::parse_signature("InsufficientBalance(uint256,uint256)")?,
+            &["5", "10"],
+        )?;
+        let reason = decode(&custom, std::slice::from_ref(&abi));
+        assert_eq!(reason.to_string(), "InsufficientBalance(5, 10)");
+        assert_eq!(decode(&custom, &[]), RevertReason::Unknown(custom));
+        assert_eq!(decode(&[], &[]).to_string(), "reverted without data");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replays_at_parent_block() -> eyre::Result<()> {
+        let tx_hash = TxHash::repeat_byte(0x66);
+        let error = alloy::hex::encode_prefixed(Revert::from("paused").abi_encode());
+        let rpc_url = mock_rpc(move |method, params| match method {
+            "eth_getTransactionByHash" => json!({
+                "type": "0x0",
+                "hash": tx_hash,
+                "nonce": "0x1",
+                "blockHash": TxHash::repeat_byte(0xbb),
+                "blockNumber": quantity(100),
+                "transactionIndex": "0x0",
+                "from": "0x0000000000000000000000000000000000000001",
+                "to": "0x0000000000000000000000000000000000000002",
+                "value": "0x0",
+                "gasPrice": "0x3b9aca00",
+                "gas": "0x186a0",
+                "input": "0x",
+                "chainId": "0x1",
+                "v": "0x25",
+                "r": "0x1",
+                "s": "0x1"
+            }),
+            "eth_call" if params[1] == quantity(99) && params[0].get("nonce").is_none() => {
+                json!({"error": {"code": 3, "message": "execution reverted: paused", "data": error}})
+            }
+            _ => Value::Null,
+        });
+
+        let reason = replay(tx_hash, rpc_url, &[]).await?;
+        assert_eq!(reason, Some(RevertReason::Error("paused".into())));
+        Ok(())
+    }
+}
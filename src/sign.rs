@@ -2,22 +2,38 @@ use std::marker::PhantomData;
 
 use super::*;
 use alloy::{
-    consensus::{self, SignableTransaction, Signed},
-    eips::Encodable2718,
-    network::TxSignerSync,
-    primitives::{Address, TxKind},
-    signers::{Signature, local::PrivateKeySigner},
+    consensus::{self, SidecarBuilder, SignableTransaction, Signed, SimpleCoder},
+    eips::{
+        Encodable2718,
+        eip2930::AccessList,
+        eip4844::{
+            BlobTransactionSidecar, Bytes48, DATA_GAS_PER_BLOB, MAX_BLOBS_PER_BLOCK_DENCUN,
+            env_settings::EnvKzgSettings,
+        },
+        eip7702::{Authorization, SignedAuthorization},
+    },
+    network::{TxSigner, TxSignerSync},
+    primitives::{Address, Bytes, TxHash},
+    signers::{Signature, SignerSync},
 };
+use serde::{Deserialize, Serialize};
 
 /// Errors for offline transaction signing.
 #[derive(Display, Error, Debug)]
 pub enum Error {
     /// Signing failed: {0}
     Sign(#[from] eyre::Report),
+    /// Failed to build the blob sidecar: {0}
+    Sidecar(eyre::Report),
+    /// {0} blobs exceed the limit of {1} per transaction
+    TooManyBlobs(usize, usize),
+    /// This transaction type cannot create contracts; a recipient address is required
+    MissingRecipient,
 }
 
 /// Common fields shared by all transaction payloads.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CommonPayload {
     /// EIP-155 chain ID for replay protection (e.g., 1 for Mainnet, 11155111 for Sepolia).
     pub chain_id: u64,
@@ -25,26 +41,218 @@ pub struct CommonPayload {
     pub nonce: u64,
     /// Maximum amount of gas units the transaction is allowed to consume.
     pub gas_limit: u64,
-    /// Recipient address of the transaction.
-    pub to: Address,
+    /// Recipient address of the transaction, or `None` to deploy a contract (CREATE).
+    pub to: Option<Address>,
     /// Amount of Wei to transfer in this transaction.
     pub value: Wei,
+    /// Calldata passed to the recipient; empty for plain ETH transfers.
+    pub input: Bytes,
+}
+
+impl CommonPayload {
+    /// Address of the contract this payload deploys when signed by `sender`.
+    ///
+    /// Returns `None` unless this is a contract creation (`to` is `None`).
+    pub fn contract_address(&self, sender: Address) -> Option<Address> {
+        self.to.is_none().then(|| sender.create(self.nonce))
+    }
 }
 
 /// Additional parameters for EIP-1559 (Type-2) transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Eip1559Payload {
     /// Maximum total fee per gas in Wei.
     pub max_fee_per_gas: u128,
     /// Maximum priority fee (tip) per gas in Wei.
     pub max_priority_fee_per_gas: u128,
+    /// Addresses and storage keys to pre-warm (EIP-2930); empty if unused.
+    pub access_list: AccessList,
+}
+
+/// Additional parameters for EIP-2930 (Type-1) transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip2930Payload {
+    /// Gas price per unit in Wei.
+    pub gas_price: u128,
+    /// Addresses and storage keys to pre-warm.
+    pub access_list: AccessList,
+}
+
+/// Additional parameters for EIP-4844 (Type-3) blob transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip4844Payload {
+    /// Maximum total fee per gas in Wei.
+    pub max_fee_per_gas: u128,
+    /// Maximum priority fee (tip) per gas in Wei.
+    pub max_priority_fee_per_gas: u128,
+    /// Maximum fee per blob gas in Wei.
+    pub max_fee_per_blob_gas: u128,
+    /// Addresses and storage keys to pre-warm (EIP-2930); empty if unused.
+    pub access_list: AccessList,
+    /// Blobs with their KZG commitments and proofs; see [`blob_sidecar`].
+    pub sidecar: BlobTransactionSidecar,
+}
+
+/// Additional parameters for EIP-7702 (Type-4) set-code transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip7702Payload {
+    /// Maximum total fee per gas in Wei.
+    pub max_fee_per_gas: u128,
+    /// Maximum priority fee (tip) per gas in Wei.
+    pub max_priority_fee_per_gas: u128,
+    /// Addresses and storage keys to pre-warm (EIP-2930); empty if unused.
+    pub access_list: AccessList,
+    /// Signed delegations to apply; see [`sign_authorization`].
+    pub authorization_list: Vec<SignedAuthorization>,
 }
 
 /// Additional parameter for legacy (pre-EIP-1559) transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LegacyPayload {
     /// Gas price per unit in Wei.
     pub gas_price: u128,
 }
 
+/// The type-specific parameters of a transaction of any supported type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UniquePayload {
+    /// Parameters of a legacy (Type-0) transaction.
+    Legacy(LegacyPayload),
+    /// Parameters of an EIP-2930 (Type-1) transaction.
+    Eip2930(Eip2930Payload),
+    /// Parameters of an EIP-1559 (Type-2) transaction.
+    Eip1559(Eip1559Payload),
+    /// Parameters of an EIP-4844 (Type-3) transaction.
+    Eip4844(Eip4844Payload),
+    /// Parameters of an EIP-7702 (Type-4) transaction.
+    Eip7702(Eip7702Payload),
+}
+
+impl UniquePayload {
+    /// Highest price per gas unit the sender may pay: the gas price or the maximum fee per gas.
+    pub fn max_fee_per_gas(&self) -> u128 {
+        match self {
+            Self::Legacy(unique) => unique.gas_price,
+            Self::Eip2930(unique) => unique.gas_price,
+            Self::Eip1559(unique) => unique.max_fee_per_gas,
+            Self::Eip4844(unique) => unique.max_fee_per_gas,
+            Self::Eip7702(unique) => unique.max_fee_per_gas,
+        }
+    }
+}
+
+/// A complete unsigned transaction whose type is chosen at runtime, e.g. read from a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedTx {
+    /// Fields shared by all transaction types.
+    pub common: CommonPayload,
+    /// Fee model and type-specific fields.
+    pub unique: UniquePayload,
+}
+
+impl UnsignedTx {
+    /// Most this transaction can cost the sender in fees, including blob gas but not its value.
+    pub fn max_fee(&self) -> Wei {
+        let mut max_fee =
+            Wei::from(self.common.gas_limit) * Wei::from(self.unique.max_fee_per_gas());
+        if let UniquePayload::Eip4844(unique) = &self.unique {
+            let blob_gas = DATA_GAS_PER_BLOB * unique.sidecar.blobs.len() as u64;
+            max_fee += Wei::from(blob_gas) * Wei::from(unique.max_fee_per_blob_gas);
+        }
+        max_fee
+    }
+
+    /// Build this transaction and sign it with any [`SignerBackend`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingRecipient`] if the type cannot create contracts but `to`
+    /// is `None`, or [`Error::Sign`] if the backend fails to produce a signature.
+    pub async fn sign_async<S>(self, signer: &S) -> Result<AnyTxSigned, Error>
+    where
+        S: SignerBackend + ?Sized,
+    {
+        let Self { common, unique } = self;
+        let signed = match unique {
+            UniquePayload::Legacy(unique) => {
+                AnyTxSigned::Legacy(common.build(unique)?.sign_async(signer).await?)
+            }
+            UniquePayload::Eip2930(unique) => {
+                AnyTxSigned::Eip2930(common.build(unique)?.sign_async(signer).await?)
+            }
+            UniquePayload::Eip1559(unique) => {
+                AnyTxSigned::Eip1559(common.build(unique)?.sign_async(signer).await?)
+            }
+            UniquePayload::Eip4844(unique) => {
+                AnyTxSigned::Eip4844(common.build(unique)?.sign_async(signer).await?)
+            }
+            UniquePayload::Eip7702(unique) => {
+                AnyTxSigned::Eip7702(common.build(unique)?.sign_async(signer).await?)
+            }
+        };
+        Ok(signed)
+    }
+}
+
+/// Build a blob sidecar by computing KZG commitments and proofs offline.
+///
+/// Each item is arbitrary data encoded into blobs of its own with [`SimpleCoder`], which
+/// keeps every field element canonical; data longer than one blob spans several.
+/// The embedded Ethereum Mainnet trusted setup is used, so no network access is required.
+///
+/// # Errors
+///
+/// Returns [`Error::TooManyBlobs`] if the encoded data needs more than
+/// [`MAX_BLOBS_PER_BLOCK_DENCUN`] blobs, or [`Error::Sidecar`] if a KZG computation fails.
+pub fn blob_sidecar<I, B>(data: I) -> Result<BlobTransactionSidecar, Error>
+where
+    I: IntoIterator<Item = B>,
+    B: AsRef<[u8]>,
+{
+    let blobs = data
+        .into_iter()
+        .flat_map(|data| SidecarBuilder::<SimpleCoder>::from_slice(data.as_ref()).take())
+        .collect::<Vec<_>>();
+    if blobs.len() > MAX_BLOBS_PER_BLOCK_DENCUN {
+        return Err(Error::TooManyBlobs(blobs.len(), MAX_BLOBS_PER_BLOCK_DENCUN));
+    }
+    let settings = EnvKzgSettings::Default.get();
+    let (mut commitments, mut proofs) = (Vec::new(), Vec::new());
+    for (i, blob) in blobs.iter().enumerate() {
+        let blob = c_kzg::Blob::new(blob.0);
+        let kzg = |e: c_kzg::Error| Error::Sidecar(eyre::eyre!("blob #{i}: {e:?}"));
+        let commitment = settings.blob_to_kzg_commitment(&blob).map_err(kzg)?.to_bytes();
+        let proof = settings.compute_blob_kzg_proof(&blob, &commitment).map_err(kzg)?.to_bytes();
+        commitments.push(Bytes48::from(commitment.into_inner()));
+        proofs.push(Bytes48::from(proof.into_inner()));
+    }
+    Ok(BlobTransactionSidecar::new(blobs, commitments, proofs))
+}
+
+/// Sign an EIP-7702 authorization tuple `(chain_id, address, nonce)` offline.
+///
+/// A `chain_id` of zero makes the authorization valid on any chain. When the authority
+/// also sends the enclosing transaction, `nonce` must be the transaction nonce plus one.
+///
+/// # Errors
+///
+/// Returns [`Error::Sign`] if the signing operation fails.
+pub fn sign_authorization<S: SignerSync + ?Sized>(
+    authorization: Authorization,
+    signer: &S,
+) -> Result<SignedAuthorization, Error> {
+    let signature = signer
+        .sign_hash_sync(&authorization.signature_hash())
+        .map_err(|e| Error::Sign(eyre::eyre!(e)))?;
+    Ok(authorization.into_signed(signature))
+}
+
 /// A typed wrapper around a transaction in its unsigned state.
 pub struct Tx<T>(T);
 
@@ -57,11 +265,45 @@ pub trait Unique {
 /// Builds a transaction of type `T` by combining a common payload with type-specific data.
 pub trait Build<T: Unique> {
     /// Combine this builder and the given unique payload to form a `Tx<T>`.
-    fn build(self, unique: T::UniquePayload) -> Tx<T>;
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingRecipient`] if a contract creation is requested
+    /// for a transaction type that requires a recipient.
+    fn build(self, unique: T::UniquePayload) -> Result<Tx<T>, Error>;
+}
+
+/// A key backend that can sign transactions built by this crate.
+///
+/// Blanket-implemented for every alloy [`TxSigner`], so local keys, keystores, mnemonics
+/// and remote or hardware signers all plug into [`Tx::sign_async`]. Backends that can
+/// also sign without I/O implement [`TxSignerSync`] and work with the blocking [`Tx::sign`].
+pub trait SignerBackend: Send + Sync {
+    /// Address of the account whose key produces the signatures.
+    fn address(&self) -> Address;
+
+    /// Sign the signature hash of `tx`, possibly waiting on an external device or service.
+    fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> impl Future<Output = Result<Signature, Error>> + Send;
+}
+
+impl<S: TxSigner<Signature> + Send + Sync> SignerBackend for S {
+    fn address(&self) -> Address {
+        TxSigner::address(self)
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> Result<Signature, Error> {
+        TxSigner::sign_transaction(self, tx).await.map_err(|e| Error::Sign(eyre::eyre!(e)))
+    }
 }
 
 impl<T: SignableTransaction<Signature>> Tx<T> {
-    /// Sign the transaction using the provided private key signer.
+    /// Sign the transaction synchronously with any alloy [`TxSignerSync`] (e.g. a local key).
     ///
     /// Consumes the unsigned `Tx<T>`, applies the digital signature,
     /// and returns a `TxSigned<T>` which can be encoded or submitted.
@@ -69,12 +311,31 @@ impl<T: SignableTransaction<Signature>> Tx<T> {
     /// # Errors
     ///
     /// Returns [`Error::Sign`] if the signing operation fails.
-    pub fn sign(mut self, signer: &PrivateKeySigner) -> Result<TxSigned<T>, Error> {
+    pub fn sign<S>(mut self, signer: &S) -> Result<TxSigned<T>, Error>
+    where
+        S: TxSignerSync<Signature> + ?Sized,
+    {
         let signature =
             signer.sign_transaction_sync(&mut self.0).map_err(|e| Error::Sign(eyre::eyre!(e)))?;
         let signed = self.0.into_signed(signature);
         Ok(TxSigned(signed))
     }
+
+    /// Sign the transaction with any [`SignerBackend`], such as a remote signing service.
+    ///
+    /// Behaves like [`Tx::sign`], but awaits the backend instead of blocking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Sign`] if the backend fails to produce a signature.
+    pub async fn sign_async<S>(mut self, signer: &S) -> Result<TxSigned<T>, Error>
+    where
+        S: SignerBackend + ?Sized,
+    {
+        let signature = signer.sign_transaction(&mut self.0).await?;
+        let signed = self.0.into_signed(signature);
+        Ok(TxSigned(signed))
+    }
 }
 
 impl<T> TxSigned<T>
@@ -90,45 +351,154 @@ where
     }
 }
 
+impl AnyTxSigned {
+    /// Encode the signed transaction into the EIP-2718 envelope format.
+    pub fn encode_2718(self) -> TxEip2718Bytes<AnyTxSigned> {
+        let bytes = match self {
+            Self::Legacy(signed) => signed.encode_2718().0,
+            Self::Eip2930(signed) => signed.encode_2718().0,
+            Self::Eip1559(signed) => signed.encode_2718().0,
+            Self::Eip4844(signed) => signed.encode_2718().0,
+            Self::Eip7702(signed) => signed.encode_2718().0,
+        };
+        TxEip2718Bytes(bytes, PhantomData)
+    }
+
+    /// Hash of the signed transaction, which for EIP-4844 excludes the blob sidecar.
+    pub fn hash(&self) -> TxHash {
+        match self {
+            Self::Legacy(signed) => *signed.0.hash(),
+            Self::Eip2930(signed) => *signed.0.hash(),
+            Self::Eip1559(signed) => *signed.0.hash(),
+            Self::Eip4844(signed) => *signed.0.hash(),
+            Self::Eip7702(signed) => *signed.0.hash(),
+        }
+    }
+}
+
 impl Unique for consensus::TxEip1559 {
     type UniquePayload = Eip1559Payload;
 }
 
+impl Unique for consensus::TxEip2930 {
+    type UniquePayload = Eip2930Payload;
+}
+
+impl Unique for consensus::TxEip4844WithSidecar {
+    type UniquePayload = Eip4844Payload;
+}
+
+impl Unique for consensus::TxEip7702 {
+    type UniquePayload = Eip7702Payload;
+}
+
 impl Unique for consensus::TxLegacy {
     type UniquePayload = LegacyPayload;
 }
 
 impl Build<consensus::TxEip1559> for CommonPayload {
-    fn build(self, unique: Eip1559Payload) -> Tx<consensus::TxEip1559> {
-        let Self { chain_id, nonce, gas_limit, to, value } = self;
-        let Eip1559Payload { max_fee_per_gas, max_priority_fee_per_gas } = unique;
+    fn build(self, unique: Eip1559Payload) -> Result<Tx<consensus::TxEip1559>, Error> {
+        let Self { chain_id, nonce, gas_limit, to, value, input } = self;
+        let Eip1559Payload { max_fee_per_gas, max_priority_fee_per_gas, access_list } = unique;
+
+        Ok(Tx(consensus::TxEip1559 {
+            chain_id,
+            nonce,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to: to.into(),
+            value,
+            input,
+            access_list,
+        }))
+    }
+}
+
+impl Build<consensus::TxEip2930> for CommonPayload {
+    fn build(self, unique: Eip2930Payload) -> Result<Tx<consensus::TxEip2930>, Error> {
+        let Self { chain_id, nonce, gas_limit, to, value, input } = self;
+        let Eip2930Payload { gas_price, access_list } = unique;
+
+        Ok(Tx(consensus::TxEip2930 {
+            chain_id,
+            nonce,
+            gas_price,
+            gas_limit,
+            to: to.into(),
+            value,
+            input,
+            access_list,
+        }))
+    }
+}
+
+impl Build<consensus::TxEip4844WithSidecar> for CommonPayload {
+    fn build(self, unique: Eip4844Payload) -> Result<Tx<consensus::TxEip4844WithSidecar>, Error> {
+        let Self { chain_id, nonce, gas_limit, to, value, input } = self;
+        let Eip4844Payload {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            access_list,
+            sidecar,
+        } = unique;
+
+        let tx = consensus::TxEip4844 {
+            chain_id,
+            nonce,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to: to.ok_or(Error::MissingRecipient)?,
+            value,
+            input,
+            access_list,
+            blob_versioned_hashes: sidecar.versioned_hashes().collect(),
+            max_fee_per_blob_gas,
+        };
+        Ok(Tx(consensus::TxEip4844WithSidecar::from_tx_and_sidecar(tx, sidecar)))
+    }
+}
+
+impl Build<consensus::TxEip7702> for CommonPayload {
+    fn build(self, unique: Eip7702Payload) -> Result<Tx<consensus::TxEip7702>, Error> {
+        let Self { chain_id, nonce, gas_limit, to, value, input } = self;
+        let Eip7702Payload {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_list,
+            authorization_list,
+        } = unique;
 
-        Tx(consensus::TxEip1559 {
+        Ok(Tx(consensus::TxEip7702 {
             chain_id,
             nonce,
             gas_limit,
             max_fee_per_gas,
             max_priority_fee_per_gas,
-            to: TxKind::Call(to),
+            to: to.ok_or(Error::MissingRecipient)?,
             value,
-            ..Default::default()
-        })
+            input,
+            access_list,
+            authorization_list,
+        }))
     }
 }
 
 impl Build<consensus::TxLegacy> for CommonPayload {
-    fn build(self, unique: LegacyPayload) -> Tx<consensus::TxLegacy> {
-        let Self { chain_id, nonce, gas_limit, to, value } = self;
+    fn build(self, unique: LegacyPayload) -> Result<Tx<consensus::TxLegacy>, Error> {
+        let Self { chain_id, nonce, gas_limit, to, value, input } = self;
         let LegacyPayload { gas_price } = unique;
 
-        Tx(consensus::TxLegacy {
+        Ok(Tx(consensus::TxLegacy {
             chain_id: Some(chain_id),
             nonce,
             gas_price,
             gas_limit,
-            to: TxKind::Call(to),
+            to: to.into(),
             value,
-            ..Default::default()
-        })
+            input,
+        }))
     }
 }
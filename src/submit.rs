@@ -31,6 +31,51 @@ where
     }
 }
 
+impl AnyTxSigned {
+    /// Decode EIP-2718 envelope–encoded bytes of any supported transaction type.
+    ///
+    /// The type is taken from the leading type byte; bytes starting with an RLP
+    /// list header (`0xc0..`) are decoded as a legacy transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`] if the type byte is unsupported or the remaining
+    /// bytes are not a valid transaction of that type.
+    pub fn decode_2718(bytes: &[u8]) -> Result<Self, Error> {
+        fn decode<T>(bytes: &[u8]) -> Result<TxSigned<T>, Error>
+        where
+            Signed<T>: Decodable2718,
+        {
+            TxEip2718Bytes::<T>::from_untyped(bytes.to_vec()).decode_2718()
+        }
+
+        match bytes.first().copied() {
+            Some(ty) if ty >= 0xc0 => decode(bytes).map(Self::Legacy),
+            Some(ty) if ty == TxType::Eip2930 => decode(bytes).map(Self::Eip2930),
+            Some(ty) if ty == TxType::Eip1559 => decode(bytes).map(Self::Eip1559),
+            Some(ty) if ty == TxType::Eip4844 => decode(bytes).map(Self::Eip4844),
+            Some(ty) if ty == TxType::Eip7702 => decode(bytes).map(Self::Eip7702),
+            Some(ty) => Err(Error::Decode(eyre::eyre!("unsupported transaction type {ty:#04x}"))),
+            None => Err(Error::Decode(eyre::eyre!("empty transaction bytes"))),
+        }
+    }
+
+    /// Submit this signed transaction to an Ethereum JSON-RPC endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Submit`] if the RPC node rejects or fails to process the transaction.
+    pub async fn submit(self, rpc_url: url::Url) -> Result<TxHash, Error> {
+        match self {
+            Self::Legacy(signed) => signed.submit(rpc_url).await,
+            Self::Eip2930(signed) => signed.submit(rpc_url).await,
+            Self::Eip1559(signed) => signed.submit(rpc_url).await,
+            Self::Eip4844(signed) => signed.submit(rpc_url).await,
+            Self::Eip7702(signed) => signed.submit(rpc_url).await,
+        }
+    }
+}
+
 impl<T> TxSigned<T>
 where
     TxEnvelope: From<Signed<T>>,
@@ -9,6 +9,8 @@ fn cli_test() -> Result<(), Box<dyn Error>> {
     cli_test_eip1559()?;
     println!("Running cli_test_legacy ...");
     cli_test_legacy()?;
+    println!("Running cli_test_eip2930 ...");
+    cli_test_eip2930()?;
     Ok(())
 }
 
@@ -68,16 +70,22 @@ fn cli_test_eip1559() -> Result<(), Box<dyn Error>> {
     // 3) Wait for confirmation
     let mut cmd_confirm = Command::cargo_bin("eth-offline-signer")?;
     let assert3 = cmd_confirm
-        .args(["confirm", "--tx-hash", tx_hash, "--rpc-url", "http://localhost:8545"])
+        .args([
+            "confirm",
+            "--tx-hash",
+            tx_hash,
+            "--output",
+            "json",
+            "--rpc-url",
+            "http://localhost:8545",
+        ])
         .assert()
-        .success()
-        .stdout(predicate::str::contains(
-            "status: Eip658(
-                    true,
-                )",
-        ));
-    let stdout3 = String::from_utf8(assert3.get_output().stdout.clone())?;
-    let tx_receipt = stdout3.trim();
+        .success();
+    let confirmation: serde_json::Value = serde_json::from_slice(&assert3.get_output().stdout)?;
+    assert_eq!(confirmation["state"], "confirmed");
+    assert_eq!(confirmation["hash"], tx_hash);
+    assert_eq!(confirmation["receipt"]["status"], "0x1");
+    let tx_receipt = serde_json::to_string_pretty(&confirmation["receipt"])?;
     println!("Confirmed transaction. Receipt:");
     println!("{tx_receipt}");
 
@@ -114,12 +122,83 @@ fn cli_test_legacy() -> Result<(), Box<dyn Error>> {
     println!("Signed transaction. EIP-2718 envelope:");
     println!("{signed_hex}");
 
+    // 2) Submit to Anvil
+    let mut cmd_submit = Command::cargo_bin("eth-offline-signer")?;
+    let assert2 = cmd_submit
+        .args(["submit", "--signed-hex", signed_hex, "--rpc-url", "http://localhost:8545"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "0xd1ea22c07a711fcd438a60da1eaa7dbe683a9b852780d5caef2badd9d9a85c05",
+        ));
+    let stdout2 = String::from_utf8(assert2.get_output().stdout.clone())?;
+    let tx_hash = stdout2.trim();
+    println!("Submitted transaction. Hash:");
+    println!("{tx_hash}");
+
+    // 3) Wait for confirmation
+    let mut cmd_confirm = Command::cargo_bin("eth-offline-signer")?;
+    let assert3 = cmd_confirm
+        .args([
+            "confirm",
+            "--tx-hash",
+            tx_hash,
+            "--output",
+            "json",
+            "--rpc-url",
+            "http://localhost:8545",
+        ])
+        .assert()
+        .success();
+    let confirmation: serde_json::Value = serde_json::from_slice(&assert3.get_output().stdout)?;
+    assert_eq!(confirmation["state"], "confirmed");
+    assert_eq!(confirmation["hash"], tx_hash);
+    assert_eq!(confirmation["receipt"]["status"], "0x1");
+    let tx_receipt = serde_json::to_string_pretty(&confirmation["receipt"])?;
+    println!("Confirmed transaction. Receipt:");
+    println!("{tx_receipt}");
+
+    Ok(())
+}
+
+fn cli_test_eip2930() -> Result<(), Box<dyn Error>> {
+    // 1) Offline sign
+    let mut cmd_sign = Command::cargo_bin("eth-offline-signer")?;
+    let assert1 = cmd_sign
+        .args([
+            "sign",
+            "--private-key",
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+            "--chain-id",
+            "31337",
+            "--nonce",
+            "2",
+            "--gas-limit",
+            "30000",
+            "--to",
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+            "--eth",
+            "0.001",
+            "eip2930",
+            "--gas-price",
+            "20000000000",
+            "--access-list",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/access_list.json"),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("01f8a8827a69028504a817c8008275309470997970c51812dc3a010c7d01b50e0d17dc79c887038d7ea4c6800080f838f79470997970c51812dc3a010c7d01b50e0d17dc79c8e1a0000000000000000000000000000000000000000000000000000000000000000080a06177a83af6e4bb117f5f116fa34d0d845a3125ba107bd56c36f4563e4bbd621ba07e4e9e3fe00bc799ab007ac440cfe8a9c8a1edc6699ca28f187a816e0ff2a347"));
+    let stdout1 = String::from_utf8(assert1.get_output().stdout.clone())?;
+    let signed_hex = stdout1.trim();
+    println!("Signed transaction. EIP-2718 envelope:");
+    println!("{signed_hex}");
+
     // 2) Submit to Anvil
     let mut cmd_submit = Command::cargo_bin("eth-offline-signer")?;
     let assert2 = cmd_submit
         .args([
             "submit",
-            "legacy",
+            "eip2930",
             "--signed-hex",
             signed_hex,
             "--rpc-url",
@@ -128,7 +207,7 @@ fn cli_test_legacy() -> Result<(), Box<dyn Error>> {
         .assert()
         .success()
         .stdout(predicate::str::starts_with(
-            "0xd1ea22c07a711fcd438a60da1eaa7dbe683a9b852780d5caef2badd9d9a85c05",
+            "0x97444dc18a871fcaf887e60a722e18e1d1d6cbb1dd2726f8a19fda65cb805d80",
         ));
     let stdout2 = String::from_utf8(assert2.get_output().stdout.clone())?;
     let tx_hash = stdout2.trim();
@@ -138,16 +217,22 @@ fn cli_test_legacy() -> Result<(), Box<dyn Error>> {
     // 3) Wait for confirmation
     let mut cmd_confirm = Command::cargo_bin("eth-offline-signer")?;
     let assert3 = cmd_confirm
-        .args(["confirm", "--tx-hash", tx_hash, "--rpc-url", "http://localhost:8545"])
+        .args([
+            "confirm",
+            "--tx-hash",
+            tx_hash,
+            "--output",
+            "json",
+            "--rpc-url",
+            "http://localhost:8545",
+        ])
         .assert()
-        .success()
-        .stdout(predicate::str::contains(
-            "status: Eip658(
-                    true,
-                )",
-        ));
-    let stdout3 = String::from_utf8(assert3.get_output().stdout.clone())?;
-    let tx_receipt = stdout3.trim();
+        .success();
+    let confirmation: serde_json::Value = serde_json::from_slice(&assert3.get_output().stdout)?;
+    assert_eq!(confirmation["state"], "confirmed");
+    assert_eq!(confirmation["hash"], tx_hash);
+    assert_eq!(confirmation["receipt"]["status"], "0x1");
+    let tx_receipt = serde_json::to_string_pretty(&confirmation["receipt"])?;
     println!("Confirmed transaction. Receipt:");
     println!("{tx_receipt}");
 